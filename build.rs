@@ -0,0 +1,88 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Bakes GIT_SHA and BUILD_TIME into the binary for `GET /version`. Either can
+// be supplied by the build environment; otherwise the SHA comes from git
+// (Railway sets RAILWAY_GIT_COMMIT_SHA when it builds without a .git dir)
+// and the time from the clock.
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=RAILWAY_GIT_COMMIT_SHA");
+    println!("cargo:rerun-if-env-changed=BUILD_TIME");
+    watch_git_head();
+
+    if let Some(sha) = env_var("GIT_SHA")
+        .or_else(|| env_var("RAILWAY_GIT_COMMIT_SHA"))
+        .or_else(git_head)
+    {
+        println!("cargo:rustc-env=GIT_SHA={}", sha);
+    }
+
+    let build_time = env_var("BUILD_TIME").unwrap_or_else(|| {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        rfc3339(secs)
+    });
+    println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+// Cargo reruns the script on every build when a watched path is missing, so
+// the .git files are only watched when they exist: HEAD for branch switches,
+// and the branch's ref file (or packed-refs) for new commits on it.
+fn watch_git_head() {
+    let head = Path::new(".git/HEAD");
+    let Ok(contents) = fs::read_to_string(head) else {
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", head.display());
+
+    if let Some(reference) = contents.trim().strip_prefix("ref: ") {
+        for path in [Path::new(".git").join(reference), PathBuf::from(".git/packed-refs")] {
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+}
+
+fn git_head() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let sha = String::from_utf8(output.stdout).ok()?;
+    Some(sha.trim().to_string()).filter(|sha| !sha.is_empty())
+}
+
+// UTC timestamp without pulling a date crate into the build; the civil-date
+// conversion is Howard Hinnant's days_from_civil inverse.
+fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3_600, rem % 3_600 / 60, rem % 60);
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
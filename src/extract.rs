@@ -0,0 +1,87 @@
+use axum::{
+    async_trait,
+    extract::{
+        FromRequest, FromRequestParts,
+        rejection::{JsonRejection, QueryRejection},
+    },
+    http::{Request, StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::handlers::{ErrorCode, ErrorResponse};
+
+/// Drop-in replacement for `axum::Json` whose rejections use the same
+/// `{ success, error, code }` envelope as the handlers' own errors.
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, B, Rejection = JsonRejection>,
+    S: Send + Sync,
+    B: Send + 'static,
+{
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            Err(rejection) => Err(json_rejection(rejection)),
+        }
+    }
+}
+
+/// Drop-in replacement for `axum::extract::Query` with the same error
+/// envelope, so `?as_array=maybe` is reported like a bad body field.
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    axum::extract::Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Query(value)) => Ok(Query(value)),
+            Err(rejection) => Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: rejection.body_text(),
+                    code: ErrorCode::InvalidInput,
+                }),
+            )),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, code) = match &rejection {
+        JsonRejection::JsonDataError(_) if rejection.body_text().contains("missing field") => {
+            (StatusCode::BAD_REQUEST, ErrorCode::MissingField)
+        }
+        JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+            (StatusCode::BAD_REQUEST, ErrorCode::InvalidInput)
+        }
+        _ => (rejection.status(), ErrorCode::InvalidInput),
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            success: false,
+            error: rejection.body_text(),
+            code,
+        }),
+    )
+}
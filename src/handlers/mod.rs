@@ -1,11 +1,53 @@
-use axum::{Json, http::StatusCode, extract::Query};
-use serde::{Serialize, Deserialize};
+use axum::{
+    Extension,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use crate::{
+    extract::{Json, Query},
+    middleware::client_ip,
+    state::{AppState, StatsCounts},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, Visitor},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
-    instruction::AccountMeta,
-    pubkey::Pubkey,
+    commitment_config::CommitmentConfig,
+    derivation_path::DerivationPath,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    address_lookup_table_account::AddressLookupTableAccount,
+    message::{Message, VersionedMessage, v0},
+    packet::PACKET_DATA_SIZE,
+    program_pack::Pack,
+    pubkey::{MAX_SEEDS, MAX_SEED_LEN, Pubkey},
+    rent::Rent,
     signature::{Keypair, Signer},
+    signer::keypair::{keypair_from_seed, keypair_from_seed_and_derivation_path},
+    system_instruction::MAX_PERMITTED_DATA_LENGTH,
+    transaction::{Transaction, VersionedTransaction},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use spl_token::instruction::TokenInstruction;
+use spl_token_2022::instruction::AuthorityType;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    num::IntErrorKind,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::{collections::HashMap, str::FromStr};
 use bs58;
 use spl_token;
 
@@ -23,19 +65,340 @@ pub struct SuccessResponse<T> {
 pub struct ErrorResponse {
     pub success: bool,
     pub error: String,
+    pub code: ErrorCode,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidPubkey,
+    InvalidSecretKey,
+    InvalidSignature,
+    InvalidBlockhash,
+    InvalidEncoding,
+    InvalidAmount,
+    InvalidInput,
+    MissingField,
+    InvalidTransaction,
+    InstructionError,
+    RpcNotConfigured,
+    RpcError,
+    RateLimited,
+    RequestTimeout,
+    SimulatedFailure,
+    Internal,
+}
+
+/// The shape every instruction builder returns, alone or in a list.
+#[derive(Serialize, Clone)]
+pub struct InstructionResponse {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaResponse>,
+    pub instruction_data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialized_instruction: Option<String>,
+}
+
+impl InstructionResponse {
+    /// Builds the response honouring the `encoding` and `serialized` query
+    /// options. This is the only constructor, so no endpoint can return an
+    /// instruction that skipped them.
+    fn with_options(
+        instruction: Instruction,
+        options: &InstructionOptions,
+    ) -> Result<Self, (StatusCode, Json<ErrorResponse>)> {
+        let serialized_instruction = serialize_instruction(&instruction, options.serialized)?;
+        let instruction_data =
+            encode_instruction_data(&instruction.data, options.encoding.as_deref())?;
+
+        Ok(InstructionResponse {
+            program_id: instruction.program_id.to_string(),
+            accounts: instruction
+                .accounts
+                .into_iter()
+                .map(|meta| AccountMetaResponse {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            instruction_data,
+            serialized_instruction,
+        })
+    }
+}
+
+/// A single-instruction builder's result. With `?as_array=true` it takes
+/// the multi-instruction endpoints' shape instead, so clients can parse
+/// both the same way.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum InstructionOutput<T = InstructionResponse> {
+    Single(T),
+    Array(InstructionArray),
+}
+
+/// `{ instructions: [...] }` plus whatever else the endpoint reports next to
+/// its instruction, such as `mint_space` or `warnings`.
+#[derive(Serialize)]
+pub struct InstructionArray {
+    pub instructions: Vec<InstructionResponse>,
+    #[serde(flatten)]
+    pub extras: serde_json::Map<String, serde_json::Value>,
+}
+
+fn instruction_output(
+    instruction: Instruction,
+    options: &InstructionOptions,
+) -> Result<InstructionOutput, (StatusCode, Json<ErrorResponse>)> {
+    instruction_output_with(instruction, options, |response| response)
+}
+
+/// Like `instruction_output`, but `single` wraps the instruction in an
+/// endpoint-specific response. With `as_array` the fields it adds beside
+/// the flattened instruction move next to the `instructions` list.
+fn instruction_output_with<T: Serialize>(
+    instruction: Instruction,
+    options: &InstructionOptions,
+    single: impl FnOnce(InstructionResponse) -> T,
+) -> Result<InstructionOutput<T>, (StatusCode, Json<ErrorResponse>)> {
+    let response = InstructionResponse::with_options(instruction, options)?;
+    if !options.as_array {
+        return Ok(InstructionOutput::Single(single(response)));
+    }
+
+    let to_object = |value: serde_json::Result<serde_json::Value>| match value {
+        Ok(serde_json::Value::Object(map)) => Ok(map),
+        _ => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: "Failed to serialize instruction response".into(),
+                code: ErrorCode::Internal,
+            }),
+        )),
+    };
+    let instruction_fields = to_object(serde_json::to_value(&response))?;
+    let mut extras = to_object(serde_json::to_value(single(response.clone())))?;
+    extras.retain(|key, _| !instruction_fields.contains_key(key));
+
+    Ok(InstructionOutput::Array(InstructionArray {
+        instructions: vec![response],
+        extras,
+    }))
+}
+
+/// The multi-instruction counterpart of `instruction_output`: each
+/// instruction honours the same `encoding` and `serialized` options. These
+/// endpoints already return a list, so `as_array` changes nothing.
+fn instruction_list(
+    instructions: Vec<Instruction>,
+    options: &InstructionOptions,
+) -> Result<Vec<InstructionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    instructions
+        .into_iter()
+        .map(|instruction| InstructionResponse::with_options(instruction, options))
+        .collect()
+}
+
+// JavaScript clients lose precision on integers above 2^53, so amounts may
+// also be sent as decimal strings.
+fn u64_from_str_or_number<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct U64Visitor;
+
+    impl<'de> Visitor<'de> for U64Visitor {
+        type Value = u64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an unsigned integer or a string containing one")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+            u64::try_from(v).map_err(|_| E::custom(format!("amount must not be negative, got {}", v)))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<u64, E> {
+            Err(E::custom(format!("amount must be an integer that fits in u64, got {}", v)))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+            v.parse::<u64>().map_err(|e| match e.kind() {
+                IntErrorKind::PosOverflow => E::custom(format!("amount {} overflows u64", v)),
+                _ => E::custom(format!("amount '{}' is not a valid unsigned integer", v)),
+            })
+        }
+    }
+
+    deserializer.deserialize_any(U64Visitor)
+}
+
+// Query strings also carry middleware switches such as `envelope`, so the
+// option structs ignore keys they don't know.
+#[derive(Deserialize)]
+pub struct InstructionOptions {
+    pub encoding: Option<String>,
+    /// Also return the whole instruction as base64 bincode, ready to be
+    /// deserialized straight into a `solana_sdk::instruction::Instruction`.
+    #[serde(default)]
+    pub serialized: bool,
+    /// Return single-instruction results as `{ instructions: [...] }`.
+    #[serde(default)]
+    pub as_array: bool,
+}
+
+fn serialize_instruction(
+    instruction: &Instruction,
+    enabled: bool,
+) -> Result<Option<String>, (StatusCode, Json<ErrorResponse>)> {
+    if !enabled {
+        return Ok(None);
+    }
+
+    bincode::serialize(instruction)
+        .map(|bytes| Some(base64::encode(bytes)))
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Failed to serialize instruction: {}", e),
+                    code: ErrorCode::Internal,
+                }),
+            )
+        })
+}
+
+fn encode_instruction_data(
+    data: &[u8],
+    encoding: Option<&str>,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    match encoding.unwrap_or("base64") {
+        "base64" => Ok(base64::encode(data)),
+        "base64url" => Ok(URL_SAFE_NO_PAD.encode(data)),
+        "hex" => Ok(hex::encode(data)),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Unsupported encoding '{}', expected \"base64\", \"base64url\" or \"hex\"",
+                    other
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        )),
+    }
+}
+
+// Pasted keys often carry stray whitespace or newlines; strip them before
+// parsing and report blank values as missing rather than malformed.
+fn require_field<'a>(
+    value: &'a str,
+    field: &str,
+) -> Result<&'a str, (StatusCode, Json<ErrorResponse>)> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(missing_field(field));
+    }
+    Ok(value)
+}
+
+// Decodes by hand rather than through `Pubkey::from_str` so the error can
+// say whether the input wasn't base58 at all or decoded to the wrong size.
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey, (StatusCode, Json<ErrorResponse>)> {
+    let invalid = |detail: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Invalid {} address: {}", field, detail),
+                code: ErrorCode::InvalidPubkey,
+            }),
+        )
+    };
+
+    let value = require_field(value, field)?;
+    let bytes = bs58::decode(value)
+        .into_vec()
+        .map_err(|e| invalid(format!("not valid base58 ({})", e)))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| invalid(format!("must decode to 32 bytes, got {}", bytes.len())))?;
+
+    Ok(Pubkey::new_from_array(bytes))
+}
+
+// `program` picks a token program per request; without it the server-wide
+// default from TOKEN_PROGRAM applies. Handlers pass the result to the
+// `spl_token_2022` builders, which accept either program id.
+fn resolve_token_program(
+    program: Option<&str>,
+    default: Pubkey,
+) -> Result<Pubkey, (StatusCode, Json<ErrorResponse>)> {
+    let Some(program) = program else {
+        return Ok(default);
+    };
+    match program {
+        "token" => Ok(spl_token::ID),
+        "token-2022" => Ok(spl_token_2022::ID),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Unknown token program '{}', expected \"token\" or \"token-2022\"",
+                    other
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        )),
+    }
 }
 
 //
 // /keypair
 //
 
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// Shorter keys are easy to guess or collide on; clients should send a UUID.
+const MIN_IDEMPOTENCY_KEY_LEN: usize = 16;
+
+// The n-th deterministic keypair uses n, little-endian and zero-padded, as
+// its 32-byte seed.
+fn next_keypair(counter: Option<&AtomicU64>) -> Keypair {
+    let Some(counter) = counter else {
+        return Keypair::new();
+    };
+
+    let mut seed = [0u8; 32];
+    seed[..8].copy_from_slice(&counter.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    keypair_from_seed(&seed).expect("32-byte seeds are always accepted")
+}
+
 #[derive(Serialize)]
 pub struct KeypairResponse {
     pub pubkey: String,
     pub secret: String,
+    /// The same secret as a 64-element array, the layout `solana-keygen`
+    /// writes to keypair files. Only present with `?format=bytes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_bytes: Option<Vec<u8>>,
 }
 
-pub async fn generate_keypair(Query(params): Query<HashMap<String, String>>) 
+pub async fn generate_keypair(
+    State(state): State<AppState>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) 
     -> Result<Json<SuccessResponse<KeypairResponse>>, (StatusCode, Json<ErrorResponse>)> 
 {
     if let Some(f) = params.get("fail") {
@@ -45,18 +408,87 @@ pub async fn generate_keypair(Query(params): Query<HashMap<String, String>>)
                 Json(ErrorResponse {
                     success: false,
                     error: "Simulated failure via query param".to_string(),
+                    code: ErrorCode::SimulatedFailure,
                 }),
             ));
         }
     }
 
-    let keypair = Keypair::new();
+    let include_bytes = match params.get("format").map(String::as_str) {
+        None | Some("base58") => false,
+        Some("bytes") => true,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "Unsupported format '{}', expected \"base58\" or \"bytes\"",
+                        other
+                    ),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+    };
+
+    // A retry carrying the same Idempotency-Key gets the keypair from the
+    // first attempt instead of a new one the client never learned about.
+    let idempotency_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    if let Some(key) = idempotency_key {
+        if key.len() < MIN_IDEMPOTENCY_KEY_LEN {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "Idempotency-Key must be at least {} characters",
+                        MIN_IDEMPOTENCY_KEY_LEN
+                    ),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+    }
+    let keypair = match idempotency_key {
+        Some(key) => {
+            let client = client_ip(&headers, peer.as_ref());
+            let bytes = state
+                .keypair_cache
+                .get_or_insert_with(&client, key, || {
+                    next_keypair(state.keypair_counter.as_deref()).to_bytes().to_vec()
+                });
+            Keypair::from_bytes(&bytes).map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        success: false,
+                        error: "Cached keypair is corrupt".into(),
+                        code: ErrorCode::Internal,
+                    }),
+                )
+            })?
+        }
+        None => next_keypair(state.keypair_counter.as_deref()),
+    };
+
     let pubkey = keypair.pubkey().to_string();
     let secret = bs58::encode(keypair.to_bytes()).into_string();
+    let secret_bytes = include_bytes.then(|| keypair.to_bytes().to_vec());
+
+    state.stats.record_keypairs(1);
 
     Ok(Json(SuccessResponse {
         success: true,
-        data: KeypairResponse { pubkey, secret },
+        data: KeypairResponse {
+            pubkey,
+            secret,
+            secret_bytes,
+        },
     }))
 }
 
@@ -65,13 +497,22 @@ pub async fn generate_keypair(Query(params): Query<HashMap<String, String>>)
 //
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CreateTokenRequest {
     pub mintAuthority: String,
     pub mint: String,
     pub decimals: u8,
+    pub program: Option<String>,
+    /// Build `initialize_mint2`, which doesn't need the rent sysvar account.
+    #[serde(default)]
+    pub use_mint2: bool,
+    /// Reject the combinations listed in `warnings` instead of only
+    /// reporting them.
+    #[serde(default)]
+    pub strict: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct AccountMetaResponse {
     pub pubkey: String,
     pub is_signer: bool,
@@ -80,129 +521,173 @@ pub struct AccountMetaResponse {
 
 #[derive(Serialize)]
 pub struct CreateTokenResponse {
-    pub program_id: String,
-    pub accounts: Vec<AccountMetaResponse>,
-    pub instruction_data: String,
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    /// Size of the mint account, for the `create_account` that must precede
+    /// this instruction.
+    pub mint_space: usize,
+    pub rent_exempt_lamports: u64,
+    /// Inputs that are valid but almost always a mistake.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+// The server can't see whether the mint already exists, but some inputs
+// are wrong on their face: the mint is a fresh account, so it can't be a
+// program, the native mint, or (usually) the authority's own wallet.
+fn suspicious_mint_inputs(mint: &Pubkey, mint_authority: &Pubkey) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if mint == mint_authority {
+        warnings.push(
+            "mint and mintAuthority are the same address; the mint should be a new account, \
+             not the authority's wallet"
+                .to_string(),
+        );
+    }
+    let reserved = [
+        (spl_token::ID, "the SPL Token program"),
+        (spl_token_2022::ID, "the Token-2022 program"),
+        (solana_sdk::system_program::ID, "the System program"),
+        (spl_token::native_mint::ID, "the native SOL mint"),
+    ];
+    for (field, pubkey) in [("mint", mint), ("mintAuthority", mint_authority)] {
+        if let Some((_, name)) = reserved.iter().find(|(id, _)| id == pubkey) {
+            warnings.push(format!("{} is {}", field, name));
+        }
+    }
+    warnings
 }
 
 pub async fn create_token(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
     Json(req): Json<CreateTokenRequest>,
-) -> Result<Json<SuccessResponse<CreateTokenResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let mint_pubkey = match Pubkey::from_str(&req.mint) {
-        Ok(p) => p,
-        Err(_) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Invalid mint pubkey".into(),
-                }),
-            ))
-        }
-    };
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<CreateTokenResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let mint_pubkey = parse_pubkey(&req.mint, "mint")?;
+    let mint_authority = parse_pubkey(&req.mintAuthority, "mintAuthority")?;
 
-    let mint_authority = match Pubkey::from_str(&req.mintAuthority) {
-        Ok(p) => p,
-        Err(_) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "Invalid mint authority pubkey".into(),
-                }),
-            ))
-        }
-    };
+    let token_program_id = resolve_token_program(req.program.as_deref(), state.token_program)?;
 
-    let token_program_id = spl_token::ID;
+    let warnings = suspicious_mint_inputs(&mint_pubkey, &mint_authority);
+    if req.strict && !warnings.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Rejected in strict mode: {}", warnings.join("; ")),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
 
-    let instruction = spl_token::instruction::initialize_mint(
-        &token_program_id,
-        &mint_pubkey,
-        &mint_authority,
-        None,
-        req.decimals,
-    )
+    let instruction = if req.use_mint2 {
+        spl_token_2022::instruction::initialize_mint2(
+            &token_program_id,
+            &mint_pubkey,
+            &mint_authority,
+            None,
+            req.decimals,
+        )
+    } else {
+        spl_token_2022::instruction::initialize_mint(
+            &token_program_id,
+            &mint_pubkey,
+            &mint_authority,
+            None,
+            req.decimals,
+        )
+    }
     .map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
                 error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
             }),
         )
     })?;
 
-    let accounts: Vec<AccountMetaResponse> = instruction
-        .accounts
-        .into_iter()
-        .map(|meta| AccountMetaResponse {
-            pubkey: meta.pubkey.to_string(),
-            is_signer: meta.is_signer,
-            is_writable: meta.is_writable,
-        })
-        .collect();
+    let data = instruction_output_with(instruction, &options, |instruction| {
+        CreateTokenResponse {
+            instruction,
+            mint_space: spl_token::state::Mint::LEN,
+            rent_exempt_lamports: Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+            warnings,
+        }
+    })?;
+
+    state.stats.record_tokens(1);
 
     Ok(Json(SuccessResponse {
         success: true,
-        data: CreateTokenResponse {
-            program_id: instruction.program_id.to_string(),
-            accounts,
-            instruction_data: base64::encode(instruction.data),
-        },
+        data,
     }))
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MintTokenRequest {
     pub mint: String,
     pub destination: String,
     pub authority: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
     pub amount: u64,
+    pub program: Option<String>,
+}
+
+/// An account of the returned instruction together with what it is for, so
+/// clients don't have to know `mint_to`'s account order.
+#[derive(Serialize)]
+pub struct LabeledAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub role: String,
 }
 
 #[derive(Serialize)]
 pub struct MintTokenResponse {
-    pub program_id: String,
-    pub accounts: Vec<AccountMetaResponse>,
-    pub instruction_data: String,
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    pub labeled_accounts: Vec<LabeledAccountMeta>,
 }
 
+// Account order of spl-token's `mint_to` with a single authority.
+const MINT_TO_ROLES: [&str; 3] = ["mint", "destination", "authority"];
+
 pub async fn mint_token(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
     Json(req): Json<MintTokenRequest>,
-) -> Result<Json<SuccessResponse<MintTokenResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let mint = Pubkey::from_str(&req.mint).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                success: false,
-                error: "Invalid mint address".into(),
-            }),
-        )
-    })?;
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<MintTokenResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let destination = parse_pubkey(&req.destination, "destination")?;
+    let authority = parse_pubkey(&req.authority, "authority")?;
 
-    let destination = Pubkey::from_str(&req.destination).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                success: false,
-                error: "Invalid destination address".into(),
-            }),
-        )
-    })?;
+    // The server can't see the current supply, so an amount that would push
+    // it past u64::MAX still only fails on-chain. Zero and anything above
+    // the operator's ceiling are caught here.
+    if req.amount == 0 {
+        return Err(invalid_amount("Amount must be greater than zero".into()));
+    }
+    if let Some(max) = state.max_mint_amount.filter(|max| req.amount > *max) {
+        return Err(invalid_amount(format!(
+            "Amount {} exceeds the configured maximum of {}",
+            req.amount, max
+        )));
+    }
 
-    let authority = Pubkey::from_str(&req.authority).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                success: false,
-                error: "Invalid authority address".into(),
-            }),
-        )
-    })?;
+    let token_program_id = resolve_token_program(req.program.as_deref(), state.token_program)?;
 
-    let instruction = spl_token::instruction::mint_to(
-        &spl_token::ID,
+    let instruction = spl_token_2022::instruction::mint_to(
+        &token_program_id,
         &mint,
         &destination,
         &authority,
@@ -215,49 +700,132 @@ pub async fn mint_token(
             Json(ErrorResponse {
                 success: false,
                 error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
             }),
         )
     })?;
 
-    let accounts = instruction
-        .accounts
-        .into_iter()
-        .map(|a| AccountMetaResponse {
-            pubkey: a.pubkey.to_string(),
-            is_signer: a.is_signer,
-            is_writable: a.is_writable,
-        })
-        .collect();
-
-    let response = MintTokenResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: base64::encode(instruction.data),
-    };
+    let data = instruction_output_with(instruction, &options, |instruction| {
+        let labeled_accounts = instruction
+            .accounts
+            .iter()
+            .zip(MINT_TO_ROLES)
+            .map(|(meta, role)| LabeledAccountMeta {
+                pubkey: meta.pubkey.clone(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+                role: role.to_string(),
+            })
+            .collect();
+        MintTokenResponse {
+            instruction,
+            labeled_accounts,
+        }
+    })?;
 
     Ok(Json(SuccessResponse {
         success: true,
-        data: response,
+        data,
     }))
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SignMessageRequest {
     pub message: String,
     pub secret: String,
+    /// How `message` is encoded: `"utf8"` (the default) signs the text
+    /// itself, `"base64"` and `"hex"` sign the decoded bytes.
+    pub message_encoding: Option<String>,
+    /// Set to false to leave `public_key` out of the response.
+    #[serde(default = "default_include_pubkey")]
+    pub include_pubkey: bool,
+}
+
+fn default_include_pubkey() -> bool {
+    true
+}
+
+fn decode_message_bytes(
+    message: &str,
+    encoding: Option<&str>,
+) -> Result<Vec<u8>, (StatusCode, Json<ErrorResponse>)> {
+    let invalid = |name: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Message is not valid {}", name),
+                code: ErrorCode::InvalidEncoding,
+            }),
+        )
+    };
+
+    match encoding.unwrap_or("utf8") {
+        "utf8" => Ok(message.as_bytes().to_vec()),
+        "base64" => base64::decode(message.trim()).map_err(|_| invalid("base64")),
+        "hex" => hex::decode(message.trim()).map_err(|_| invalid("hex")),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Unsupported message_encoding '{}', expected \"utf8\", \"base64\" or \"hex\"",
+                    other
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        )),
+    }
 }
 
 #[derive(Serialize)]
 pub struct SignMessageResponse {
     pub signature: String,
-    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
     pub message: String,
 }
 
+fn check_message_size(message: &str, max: usize) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if message.len() <= max {
+        return Ok(());
+    }
+    Err((
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            success: false,
+            error: format!("Message too large: {} bytes, the limit is {}", message.len(), max),
+            code: ErrorCode::InvalidInput,
+        }),
+    ))
+}
+
 pub async fn sign_message(
+    State(state): State<AppState>,
     Json(req): Json<SignMessageRequest>,
 ) -> Result<Json<SuccessResponse<SignMessageResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let secret_bytes = bs58::decode(&req.secret)
+    check_message_size(&req.message, state.max_message_bytes)?;
+    let keypair = decode_keypair(&req.secret)?;
+
+    let message_bytes = decode_message_bytes(&req.message, req.message_encoding.as_deref())?;
+    let signature = keypair.sign_message(&message_bytes);
+
+    state.stats.record_signatures(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SignMessageResponse {
+            signature: base64::encode(signature),
+            public_key: req.include_pubkey.then(|| keypair.pubkey().to_string()),
+            message: req.message,
+        },
+    }))
+}
+
+fn decode_keypair(secret: &str) -> Result<Keypair, (StatusCode, Json<ErrorResponse>)> {
+    let secret = require_field(secret, "secret")?;
+    let secret_bytes = bs58::decode(secret)
         .into_vec()
         .map_err(|_| {
             (
@@ -265,6 +833,7 @@ pub async fn sign_message(
                 Json(ErrorResponse {
                     success: false,
                     error: "Invalid base58 secret key".into(),
+                    code: ErrorCode::InvalidSecretKey,
                 }),
             )
         })?;
@@ -275,28 +844,41 @@ pub async fn sign_message(
             Json(ErrorResponse {
                 success: false,
                 error: "Failed to deserialize secret key".into(),
+                code: ErrorCode::InvalidSecretKey,
             }),
         )
     })?;
 
-    let message_bytes = req.message.as_bytes();
-    let signature = keypair.sign_message(message_bytes);
+    // A 64-byte blob with the right length can still be garbage, e.g. all
+    // zeros, or a public half that isn't derived from the secret half.
+    // Signing with it would produce signatures nobody can use.
+    let pubkey = keypair.pubkey();
+    let derived = ed25519_dalek::PublicKey::from(keypair.secret());
+    if pubkey == Pubkey::default()
+        || !pubkey.is_on_curve()
+        || derived.as_bytes() != &pubkey.to_bytes()
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Invalid secret key material".into(),
+                code: ErrorCode::InvalidSecretKey,
+            }),
+        ));
+    }
 
-    Ok(Json(SuccessResponse {
-        success: true,
-        data: SignMessageResponse {
-            signature: base64::encode(signature),
-            public_key: keypair.pubkey().to_string(),
-            message: req.message,
-        },
-    }))
+    Ok(keypair)
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct VerifyMessageRequest {
     pub message: String,
     pub signature: String,
     pub pubkey: String,
+    #[serde(rename = "includeDigest", default)]
+    pub include_digest: bool,
 }
 
 #[derive(Serialize)]
@@ -304,101 +886,135 @@ pub struct VerifyMessageResponse {
     pub valid: bool,
     pub message: String,
     pub pubkey: String,
+    #[serde(rename = "messageSha256", skip_serializing_if = "Option::is_none")]
+    pub message_sha256: Option<String>,
+    #[serde(rename = "messageLength", skip_serializing_if = "Option::is_none")]
+    pub message_length: Option<usize>,
+    /// Why verification failed; absent when `valid` is true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 pub async fn verify_message(
+    State(state): State<AppState>,
     Json(req): Json<VerifyMessageRequest>,
 ) -> Result<Json<SuccessResponse<VerifyMessageResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let pubkey = Pubkey::from_str(&req.pubkey).map_err(|_| {
+    check_message_size(&req.message, state.max_message_bytes)?;
+    let pubkey = parse_pubkey(&req.pubkey, "pubkey")?;
+
+    let signature = decode_signature(&req.signature)?;
+    let dalek_pubkey = dalek_public_key(&pubkey)?;
+
+    let reason = dalek_pubkey
+        .verify_strict(req.message.as_bytes(), &signature)
+        .err()
+        .map(|_| verification_failure_reason(&dalek_pubkey, req.message.as_bytes(), &signature));
+    let valid = reason.is_none();
+
+    let (message_sha256, message_length) = if req.include_digest {
+        let digest = solana_sdk::hash::hash(req.message.as_bytes());
+        (Some(hex::encode(digest.to_bytes())), Some(req.message.len()))
+    } else {
+        (None, None)
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VerifyMessageResponse {
+            valid,
+            message: req.message,
+            pubkey: req.pubkey,
+            message_sha256,
+            message_length,
+            reason,
+        },
+    }))
+}
+
+// dalek's strict verification error doesn't say what went wrong, so retry
+// with the lenient check: if that passes, the signature itself is fine but
+// non-canonical or made with a weak key, which strict mode rejects.
+fn verification_failure_reason(
+    pubkey: &ed25519_dalek::PublicKey,
+    message: &[u8],
+    signature: &ed25519_dalek::Signature,
+) -> String {
+    use ed25519_dalek::Verifier;
+
+    if pubkey.verify(message, signature).is_ok() {
+        "signature is non-canonical or the public key is weak".into()
+    } else {
+        "signature does not match the message and public key".into()
+    }
+}
+
+fn decode_signature(
+    encoded: &str,
+) -> Result<ed25519_dalek::Signature, (StatusCode, Json<ErrorResponse>)> {
+    let signature_bytes = base64::decode(encoded).map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid pubkey".into(),
+                error: "Invalid base64 signature".into(),
+                code: ErrorCode::InvalidSignature,
             }),
         )
     })?;
 
-    let signature_bytes = base64::decode(&req.signature).map_err(|_| {
-        (
+    if signature_bytes.len() != 64 {
+        return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid base64 signature".into(),
+                error: format!("Signature must be 64 bytes, got {}", signature_bytes.len()),
+                code: ErrorCode::InvalidSignature,
             }),
-        )
-    })?;
+        ));
+    }
 
-    let signature = ed25519_dalek::Signature::from_bytes(&signature_bytes).map_err(|_| {
+    ed25519_dalek::Signature::from_bytes(&signature_bytes).map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
                 error: "Invalid signature format".into(),
+                code: ErrorCode::InvalidSignature,
             }),
         )
-    })?;
+    })
+}
 
-    let dalek_pubkey = ed25519_dalek::PublicKey::from_bytes(pubkey.as_ref()).map_err(|_| {
+fn dalek_public_key(
+    pubkey: &Pubkey,
+) -> Result<ed25519_dalek::PublicKey, (StatusCode, Json<ErrorResponse>)> {
+    ed25519_dalek::PublicKey::from_bytes(pubkey.as_ref()).map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
                 error: "Invalid public key format".into(),
+                code: ErrorCode::InvalidPubkey,
             }),
         )
-    })?;
-
-    let valid = dalek_pubkey
-        .verify_strict(req.message.as_bytes(), &signature)
-        .is_ok();
-
-    Ok(Json(SuccessResponse {
-        success: true,
-        data: VerifyMessageResponse {
-            valid,
-            message: req.message,
-            pubkey: req.pubkey,
-        },
-    }))
+    })
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SendSolRequest {
     pub from: String,
     pub to: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
     pub lamports: u64,
 }
 
-#[derive(Serialize)]
-pub struct SendSolResponse {
-    pub program_id: String,
-    pub accounts: Vec<String>,
-    pub instruction_data: String,
-}
-
 pub async fn send_sol(
+    Query(options): Query<InstructionOptions>,
     Json(req): Json<SendSolRequest>,
-) -> Result<Json<SuccessResponse<SendSolResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let from_pubkey = Pubkey::from_str(&req.from).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                success: false,
-                error: "Invalid 'from' address".into(),
-            }),
-        )
-    })?;
-
-    let to_pubkey = Pubkey::from_str(&req.to).map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                success: false,
-                error: "Invalid 'to' address".into(),
-            }),
-        )
-    })?;
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let from_pubkey = parse_pubkey(&req.from, "from")?;
+    let to_pubkey = parse_pubkey(&req.to, "to")?;
 
     let instruction = solana_sdk::system_instruction::transfer(
         &from_pubkey,
@@ -406,125 +1022,3829 @@ pub async fn send_sol(
         req.lamports,
     );
 
-    let accounts = instruction
-        .accounts
-        .iter()
-        .map(|meta| meta.pubkey.to_string())
-        .collect::<Vec<_>>();
-
-    let response = SendSolResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: base64::encode(instruction.data),
-    };
-
     Ok(Json(SuccessResponse {
         success: true,
-        data: response,
+        data: instruction_output(instruction, &options)?,
     }))
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SendTokenRequest {
+    /// Recipient wallet; the tokens land in its associated token account.
     pub destination: String,
     pub mint: String,
     pub owner: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
     pub amount: u64,
+    pub program: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct SendTokenResponse {
-    pub program_id: String,
-    pub accounts: Vec<AccountMetaSimple>,
-    pub instruction_data: String,
-}
-
-#[derive(Serialize)]
-pub struct AccountMetaSimple {
-    pub pubkey: String,
-    pub isSigner: bool,
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    /// The owner's associated token account the tokens leave from.
+    pub source: String,
+    /// The destination wallet's associated token account.
+    pub destination_ata: String,
 }
 
 pub async fn send_token(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
     Json(req): Json<SendTokenRequest>,
-) -> Result<Json<SuccessResponse<SendTokenResponse>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<SendTokenResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
     // Parse all input pubkeys
-    let destination = Pubkey::from_str(&req.destination).map_err(|_| {
+    let destination = parse_pubkey(&req.destination, "destination")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let token_program_id = resolve_token_program(req.program.as_deref(), state.token_program)?;
+
+    // transfer_checked moves tokens between token accounts, not wallets:
+    // both ends are the wallets' associated token accounts for this mint.
+    let source = get_associated_token_address_with_program_id(&owner, &mint, &token_program_id);
+    let destination_ata =
+        get_associated_token_address_with_program_id(&destination, &mint, &token_program_id);
+
+    let instruction = spl_token_2022::instruction::transfer_checked(
+        &token_program_id,
+        &source,
+        &mint,
+        &destination_ata,
+        &owner,
+        &[],              // multisig signer pubkeys if any
+        req.amount,
+        state.default_token_decimals,
+    )
+    .map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid destination address".into(),
+                error: format!("Instruction error: {}", e),
+                code: ErrorCode::InstructionError,
             }),
         )
     })?;
 
-    let mint = Pubkey::from_str(&req.mint).map_err(|_| {
+    let data = instruction_output_with(instruction, &options, |instruction| SendTokenResponse {
+        instruction,
+        source: source.to_string(),
+        destination_ata: destination_ata.to_string(),
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /transaction/build
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountMetaRequest {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InstructionRequest {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaRequest>,
+    pub data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BuildTransactionRequest {
+    pub fee_payer: String,
+    pub recent_blockhash: Option<String>,
+    /// Durable nonce value stored in a nonce account, used in place of
+    /// `recent_blockhash`. The caller reads it from the nonce account; the
+    /// first instruction must be the `advance_nonce_account` for that
+    /// account, which is prepended when `nonce_account` is given.
+    #[serde(rename = "nonceValue")]
+    pub nonce_value: Option<String>,
+    pub nonce_account: Option<String>,
+    pub nonce_authority: Option<String>,
+    pub instructions: Vec<InstructionRequest>,
+    /// `"legacy"` (the default) or `"0"` for a versioned message.
+    pub version: Option<String>,
+    /// Lookup tables a v0 message may load accounts from. Only the
+    /// accounts found in a table and not signing are moved out of the
+    /// static key list; the rest stay static.
+    pub address_lookup_tables: Option<Vec<AddressLookupTableRequest>>,
+}
+
+/// A lookup table's address and its current contents, as read from chain.
+/// The compiler needs the contents to work out which indexes to reference.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddressLookupTableRequest {
+    pub key: String,
+    pub addresses: Vec<String>,
+}
+
+fn parse_lookup_table(
+    index: usize,
+    table: &AddressLookupTableRequest,
+) -> Result<AddressLookupTableAccount, (StatusCode, Json<ErrorResponse>)> {
+    let key = parse_pubkey(&table.key, &format!("address_lookup_tables[{}].key", index))?;
+    let addresses = table
+        .addresses
+        .iter()
+        .enumerate()
+        .map(|(j, address)| {
+            parse_pubkey(address, &format!("address_lookup_tables[{}].addresses[{}]", index, j))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AddressLookupTableAccount { key, addresses })
+}
+
+#[derive(Serialize)]
+pub struct BuildTransactionResponse {
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub struct TransactionOptions {
+    pub encoding: Option<String>,
+}
+
+fn encode_transaction_bytes(
+    bytes: &[u8],
+    encoding: Option<&str>,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    match encoding.unwrap_or("base64") {
+        "base64" => Ok(base64::encode(bytes)),
+        "base64url" => Ok(URL_SAFE_NO_PAD.encode(bytes)),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Unsupported encoding '{}', expected \"base64\" or \"base64url\"",
+                    other
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        )),
+    }
+}
+
+fn decode_transaction_bytes(
+    encoded: &str,
+    encoding: Option<&str>,
+) -> Result<Vec<u8>, (StatusCode, Json<ErrorResponse>)> {
+    let decoded = match encoding.unwrap_or("base64") {
+        "base64" => base64::decode(encoded),
+        "base64url" => URL_SAFE_NO_PAD.decode(encoded),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "Unsupported encoding '{}', expected \"base64\" or \"base64url\"",
+                        other
+                    ),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ))
+        }
+    };
+
+    decoded.map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid mint address".into(),
+                error: "Invalid transaction encoding".into(),
+                code: ErrorCode::InvalidEncoding,
             }),
         )
-    })?;
+    })
+}
+
+fn parse_instruction(
+    index: usize,
+    ix: &InstructionRequest,
+) -> Result<Instruction, (StatusCode, Json<ErrorResponse>)> {
+    parse_instruction_fields(&format!("instructions[{}].", index), ix)
+}
+
+// `prefix` locates the instruction in the request for error messages, e.g.
+// "instructions[2]." or "" when it is the whole body.
+fn parse_instruction_fields(
+    prefix: &str,
+    ix: &InstructionRequest,
+) -> Result<Instruction, (StatusCode, Json<ErrorResponse>)> {
+    let program_id = parse_pubkey(&ix.program_id, &format!("{}program_id", prefix))?;
 
-    let owner = Pubkey::from_str(&req.owner).map_err(|_| {
+    let mut accounts = Vec::with_capacity(ix.accounts.len());
+    for (j, meta) in ix.accounts.iter().enumerate() {
+        let pubkey = parse_pubkey(&meta.pubkey, &format!("{}accounts[{}].pubkey", prefix, j))?;
+        accounts.push(AccountMeta {
+            pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        });
+    }
+
+    let data = base64::decode(&ix.data).map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid owner address".into(),
+                error: format!("Invalid base64 in {}data", prefix),
+                code: ErrorCode::InvalidEncoding,
             }),
         )
     })?;
 
-    // 👇 In transfer_checked, source is owner's associated token account.
-    let source = Pubkey::from_str(&req.destination).map_err(|_| {
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+pub async fn build_transaction(
+    Query(options): Query<TransactionOptions>,
+    Json(req): Json<BuildTransactionRequest>,
+) -> Result<Json<SuccessResponse<BuildTransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let fee_payer = parse_pubkey(&req.fee_payer, "fee_payer")?;
+
+    let (blockhash, field) = match (&req.recent_blockhash, &req.nonce_value) {
+        (Some(blockhash), None) => (blockhash, "recent blockhash"),
+        (None, Some(nonce)) => (nonce, "nonce value"),
+        (Some(_), Some(_)) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "Provide either recent_blockhash or nonceValue, not both".into(),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ))
+        }
+        (None, None) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "Missing required field: recent_blockhash or nonceValue".into(),
+                    code: ErrorCode::MissingField,
+                }),
+            ))
+        }
+    };
+
+    let recent_blockhash = Hash::from_str(blockhash).map_err(|_| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: "Invalid source token address".into(),
+                error: format!("Invalid {}", field),
+                code: ErrorCode::InvalidBlockhash,
             }),
         )
     })?;
 
-    let instruction = spl_token::instruction::transfer_checked(
-        &spl_token::ID,
-        &source,
-        &mint,
-        &destination,
+    let advance_nonce = match (&req.nonce_account, &req.nonce_authority) {
+        (Some(account), Some(authority)) => {
+            if req.nonce_value.is_none() {
+                return Err(missing_field("nonceValue"));
+            }
+            let account = parse_pubkey(account, "nonce_account")?;
+            let authority = parse_pubkey(authority, "nonce_authority")?;
+            Some(solana_sdk::system_instruction::advance_nonce_account(&account, &authority))
+        }
+        (None, None) => None,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "nonce_account and nonce_authority must be provided together".into(),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ))
+        }
+    };
+
+    let instructions = advance_nonce
+        .map(Ok)
+        .into_iter()
+        .chain(
+            req.instructions
+                .iter()
+                .enumerate()
+                .map(|(i, ix)| parse_instruction(i, ix)),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let message_bytes = match req.version.as_deref().map(str::trim) {
+        None | Some("legacy") => {
+            if req.address_lookup_tables.is_some() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        success: false,
+                        error: "address_lookup_tables require version \"0\"; legacy messages \
+                                can't load accounts from lookup tables"
+                            .into(),
+                        code: ErrorCode::InvalidInput,
+                    }),
+                ));
+            }
+            Message::new_with_blockhash(&instructions, Some(&fee_payer), &recent_blockhash)
+                .serialize()
+        }
+        Some("0") => {
+            let tables = req
+                .address_lookup_tables
+                .iter()
+                .flatten()
+                .enumerate()
+                .map(|(i, table)| parse_lookup_table(i, table))
+                .collect::<Result<Vec<_>, _>>()?;
+            let message =
+                v0::Message::try_compile(&fee_payer, &instructions, &tables, recent_blockhash)
+                    .map_err(|e| {
+                        invalid_transaction(format!("Failed to compile v0 message: {}", e))
+                    })?;
+            VersionedMessage::V0(message).serialize()
+        }
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "Unsupported version '{}', expected \"legacy\" or \"0\"",
+                        other
+                    ),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: BuildTransactionResponse {
+            message: encode_transaction_bytes(&message_bytes, options.encoding.as_deref())?,
+        },
+    }))
+}
+
+//
+// /account/close-to-owner
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloseToOwnerRequest {
+    pub account: String,
+    pub owner: String,
+}
+
+pub async fn close_account_to_owner(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<CloseToOwnerRequest>,
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let account = parse_pubkey(&req.account, "account")?;
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    // Rent goes back to the owner, who is also the closing authority.
+    let instruction = spl_token_2022::instruction::close_account(
+        &state.token_program,
+        &account,
         &owner,
-        &[],              // multisig signer pubkeys if any
-        req.amount,
-        6,                // decimals (defaulting to 6)
+        &owner,
+        &[],
     )
     .map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 success: false,
-                error: format!("Instruction error: {}", e),
+                error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
             }),
         )
     })?;
 
-    let accounts = instruction
-        .accounts
-        .into_iter()
-        .map(|meta| AccountMetaSimple {
-            pubkey: meta.pubkey.to_string(),
-            isSigner: meta.is_signer,
-        })
-        .collect();
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: instruction_output(instruction, &options)?,
+    }))
+}
+
+//
+// /message/canonicalize
+//
+
+#[derive(Serialize)]
+pub struct CanonicalizeResponse {
+    pub canonical: String,
+}
+
+pub async fn canonicalize_message(
+    Json(value): Json<serde_json::Value>,
+) -> Result<Json<SuccessResponse<CanonicalizeResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let mut canonical = String::new();
+    write_canonical_json(&value, &mut canonical);
 
     Ok(Json(SuccessResponse {
         success: true,
-        data: SendTokenResponse {
-            program_id: instruction.program_id.to_string(),
-            accounts,
-            instruction_data: base64::encode(instruction.data),
-        },
+        data: CanonicalizeResponse { canonical },
     }))
 }
+
+// RFC 8785 (JCS): object members sorted by UTF-16 code units, no insignificant
+// whitespace, and numbers printed the way ECMAScript's Number.toString does.
+fn write_canonical_json(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        // Integers too: JCS consumers read numbers as IEEE doubles, so
+        // anything above 2^53 is printed as the double it rounds to.
+        Value::Number(n) => out.push_str(&format_es_number(n.as_f64().unwrap_or(0.0))),
+        Value::String(s) => out.push_str(&serde_json::to_string(s).unwrap_or_default()),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key).unwrap_or_default());
+                out.push(':');
+                write_canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn format_es_number(f: f64) -> String {
+    if f == 0.0 {
+        return "0".into();
+    }
+
+    // `{:e}` gives the shortest round-tripping digits, e.g. "-1.25e-7".
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().unwrap_or(0) + 1;
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, (n - 1).abs())
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], sign, (n - 1).abs())
+        }
+    };
+
+    if f < 0.0 {
+        format!("-{}", body)
+    } else {
+        body
+    }
+}
+
+//
+// /token/create/with-account
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateTokenWithAccountRequest {
+    pub payer: String,
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: String,
+    pub mint: String,
+    pub decimals: u8,
+}
+
+#[derive(Serialize)]
+pub struct CreateTokenWithAccountResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn create_token_with_account(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<CreateTokenWithAccountRequest>,
+) -> Result<Json<SuccessResponse<CreateTokenWithAccountResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let payer = parse_pubkey(&req.payer, "payer")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let mint_authority = parse_pubkey(&req.mint_authority, "mintAuthority")?;
+
+    let space = spl_token::state::Mint::LEN;
+    let lamports = Rent::default().minimum_balance(space);
+
+    let create_account = solana_sdk::system_instruction::create_account(
+        &payer,
+        &mint,
+        lamports,
+        space as u64,
+        &state.token_program,
+    );
+
+    let initialize_mint = spl_token_2022::instruction::initialize_mint(
+        &state.token_program,
+        &mint,
+        &mint_authority,
+        None,
+        req.decimals,
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    state.stats.record_tokens(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: CreateTokenWithAccountResponse {
+            instructions: instruction_list(vec![create_account, initialize_mint], &options)?,
+        },
+    }))
+}
+
+//
+// /token/approve/batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DelegateAllowance {
+    pub delegate: String,
+    pub amount: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApproveBatchRequest {
+    pub source: String,
+    pub mint: String,
+    pub owner: String,
+    pub decimals: u8,
+    pub delegates: Vec<DelegateAllowance>,
+}
+
+#[derive(Serialize)]
+pub struct ApproveBatchResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn approve_batch(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<ApproveBatchRequest>,
+) -> Result<Json<SuccessResponse<ApproveBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let source = parse_pubkey(&req.source, "source")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let mut instructions = Vec::with_capacity(req.delegates.len());
+    for (i, entry) in req.delegates.iter().enumerate() {
+        let delegate = parse_pubkey(&entry.delegate, &format!("delegates[{}].delegate", i))?;
+
+        if entry.amount == 0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Amount must be greater than zero at index {}", i),
+                    code: ErrorCode::InvalidAmount,
+                }),
+            ));
+        }
+
+        let instruction = spl_token_2022::instruction::approve_checked(
+            &state.token_program,
+            &source,
+            &mint,
+            &delegate,
+            &owner,
+            &[],
+            entry.amount,
+            req.decimals,
+        )
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Failed to create instruction at index {}: {}", i, e),
+                    code: ErrorCode::InstructionError,
+                }),
+            )
+        })?;
+
+        instructions.push(instruction);
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: ApproveBatchResponse {
+            instructions: instruction_list(instructions, &options)?,
+        },
+    }))
+}
+
+//
+// /transaction/fit-check
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FitCheckRequest {
+    pub instructions: Vec<InstructionRequest>,
+    pub signer_count: usize,
+}
+
+#[derive(Serialize)]
+pub struct FitCheckResponse {
+    pub fits: bool,
+    #[serde(rename = "estimatedSize")]
+    pub estimated_size: usize,
+    #[serde(rename = "suggestedSplitIndex", skip_serializing_if = "Option::is_none")]
+    pub suggested_split_index: Option<usize>,
+    /// The first instruction too large for a transaction even on its own.
+    /// No split can help then, so `suggestedSplitIndex` is left out.
+    #[serde(rename = "oversizedInstructionIndex", skip_serializing_if = "Option::is_none")]
+    pub oversized_instruction_index: Option<usize>,
+}
+
+pub async fn fit_check(
+    Json(req): Json<FitCheckRequest>,
+) -> Result<Json<SuccessResponse<FitCheckResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let instructions = req
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(i, ix)| parse_instruction(i, ix))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let estimated_size = estimate_transaction_size(&instructions, req.signer_count)
+        .ok_or_else(|| arithmetic_overflow("transaction size exceeds usize"))?;
+    let fits = estimated_size <= PACKET_DATA_SIZE;
+
+    let oversized_instruction_index = if fits {
+        None
+    } else {
+        instructions.iter().position(|instruction| {
+            estimate_transaction_size(std::slice::from_ref(instruction), req.signer_count)
+                .is_none_or(|size| size > PACKET_DATA_SIZE)
+        })
+    };
+
+    // The split index is the first instruction that no longer fits alongside
+    // the ones before it, i.e. where the second transaction should begin.
+    let suggested_split_index = if fits || oversized_instruction_index.is_some() {
+        None
+    } else {
+        (1..instructions.len())
+            .take_while(|&k| {
+                estimate_transaction_size(&instructions[..k], req.signer_count)
+                    .is_some_and(|size| size <= PACKET_DATA_SIZE)
+            })
+            .last()
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: FitCheckResponse {
+            fits,
+            estimated_size,
+            suggested_split_index,
+            oversized_instruction_index,
+        },
+    }))
+}
+
+// `signer_count` comes straight from the request, so the sum is checked.
+fn estimate_transaction_size(instructions: &[Instruction], signer_count: usize) -> Option<usize> {
+    let message = Message::new(instructions, None);
+    signer_count
+        .checked_mul(64)?
+        .checked_add(compact_u16_len(signer_count))?
+        .checked_add(message.serialize().len())
+}
+
+fn compact_u16_len(value: usize) -> usize {
+    match value {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+//
+// /account/rent
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RentMinimumRequest {
+    pub data_len: usize,
+}
+
+#[derive(Serialize)]
+pub struct RentMinimumResponse {
+    pub lamports: u64,
+}
+
+pub async fn rent_minimum(
+    Json(req): Json<RentMinimumRequest>,
+) -> Result<Json<SuccessResponse<RentMinimumResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    if req.data_len as u64 > MAX_PERMITTED_DATA_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "data_len must be at most {} bytes, got {}",
+                    MAX_PERMITTED_DATA_LENGTH, req.data_len
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: RentMinimumResponse {
+            lamports: Rent::default().minimum_balance(req.data_len),
+        },
+    }))
+}
+
+//
+// /keypair/indexed
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexedKeypairRequest {
+    #[serde(rename = "masterSeed")]
+    pub master_seed: String,
+    pub index: u32,
+}
+
+// BIP32 seeds are 128 to 512 bits; anything shorter is guessable.
+const MIN_MASTER_SEED_BYTES: usize = 16;
+const MAX_MASTER_SEED_BYTES: usize = 64;
+
+pub async fn generate_indexed_keypair(
+    State(state): State<AppState>,
+    Json(req): Json<IndexedKeypairRequest>,
+) -> Result<Json<SuccessResponse<KeypairResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let seed = hex::decode(&req.master_seed).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Invalid hex master seed".into(),
+                code: ErrorCode::InvalidEncoding,
+            }),
+        )
+    })?;
+    if !(MIN_MASTER_SEED_BYTES..=MAX_MASTER_SEED_BYTES).contains(&seed.len()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "masterSeed must be {} to {} bytes, got {}",
+                    MIN_MASTER_SEED_BYTES,
+                    MAX_MASTER_SEED_BYTES,
+                    seed.len()
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    // Hardened child indexes only cover the lower 31 bits.
+    if req.index >= 1 << 31 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Index must be below 2^31, got {}", req.index),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    // m/44'/501'/index'/0'
+    let path = DerivationPath::new_bip44(Some(req.index), Some(0));
+    let keypair = keypair_from_seed_and_derivation_path(&seed, Some(path)).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to derive keypair: {}", e),
+                code: ErrorCode::InvalidSecretKey,
+            }),
+        )
+    })?;
+
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: KeypairResponse {
+            pubkey: keypair.pubkey().to_string(),
+            secret: bs58::encode(keypair.to_bytes()).into_string(),
+            secret_bytes: None,
+        },
+    }))
+}
+
+//
+// /token/revoke/batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RevokeBatchRequest {
+    pub owner: String,
+    pub sources: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RevokeBatchResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn revoke_batch(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<RevokeBatchRequest>,
+) -> Result<Json<SuccessResponse<RevokeBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let mut instructions = Vec::with_capacity(req.sources.len());
+    for (i, source) in req.sources.iter().enumerate() {
+        let source = parse_pubkey(source, &format!("sources[{}]", i))?;
+
+        let instruction =
+            spl_token_2022::instruction::revoke(&state.token_program, &source, &owner, &[])
+                .map_err(|e| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse {
+                            success: false,
+                            error: format!("Failed to create instruction at index {}: {}", i, e),
+                            code: ErrorCode::InstructionError,
+                        }),
+                    )
+                })?;
+
+        instructions.push(instruction);
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: RevokeBatchResponse {
+            instructions: instruction_list(instructions, &options)?,
+        },
+    }))
+}
+
+//
+// /benchmark/keypair
+//
+
+#[derive(Deserialize)]
+pub struct BenchmarkParams {
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct BenchmarkResponse {
+    pub keypairs: u64,
+    pub elapsed_ms: u64,
+    pub keys_per_second: f64,
+}
+
+const DEFAULT_BENCHMARK_MS: u64 = 1000;
+const MAX_BENCHMARK_MS: u64 = 5000;
+
+pub async fn benchmark_keypair(
+    Query(params): Query<BenchmarkParams>,
+) -> Result<Json<SuccessResponse<BenchmarkResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let duration_ms = params.duration_ms.unwrap_or(DEFAULT_BENCHMARK_MS);
+    if duration_ms == 0 || duration_ms > MAX_BENCHMARK_MS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("duration_ms must be between 1 and {}", MAX_BENCHMARK_MS),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let duration = std::time::Duration::from_millis(duration_ms);
+    let (keypairs, elapsed) = tokio::task::spawn_blocking(move || {
+        let start = std::time::Instant::now();
+        let mut keypairs = 0u64;
+        while start.elapsed() < duration {
+            let _ = Keypair::new();
+            keypairs += 1;
+        }
+        (keypairs, start.elapsed())
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Benchmark task failed: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: BenchmarkResponse {
+            keypairs,
+            elapsed_ms: elapsed.as_millis() as u64,
+            keys_per_second: keypairs as f64 / elapsed.as_secs_f64(),
+        },
+    }))
+}
+
+//
+// /send/token/ata
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SendTokenAtaRequest {
+    pub sender: String,
+    pub recipient: String,
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+#[derive(Serialize)]
+pub struct SendTokenAtaResponse {
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    /// The sender's associated token account the tokens leave from.
+    pub source: String,
+    /// The recipient's associated token account.
+    pub destination_ata: String,
+}
+
+pub async fn send_token_ata(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<SendTokenAtaRequest>,
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<SendTokenAtaResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let sender = parse_pubkey(&req.sender, "sender")?;
+    let recipient = parse_pubkey(&req.recipient, "recipient")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+
+    let sender_ata =
+        get_associated_token_address_with_program_id(&sender, &mint, &state.token_program);
+    let recipient_ata =
+        get_associated_token_address_with_program_id(&recipient, &mint, &state.token_program);
+
+    let instruction = spl_token_2022::instruction::transfer_checked(
+        &state.token_program,
+        &sender_ata,
+        &mint,
+        &recipient_ata,
+        &sender,
+        &[],
+        req.amount,
+        req.decimals,
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Instruction error: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    let data = instruction_output_with(instruction, &options, |instruction| SendTokenAtaResponse {
+        instruction,
+        source: sender_ata.to_string(),
+        destination_ata: recipient_ata.to_string(),
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /message/verify/pda-linked
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyPdaLinkedRequest {
+    pub pda: String,
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    pub seeds: Vec<String>,
+    pub message: String,
+    pub pubkey: String,
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct VerifyPdaLinkedResponse {
+    pub valid: bool,
+    #[serde(rename = "pdaMatches")]
+    pub pda_matches: bool,
+    #[serde(rename = "signatureValid")]
+    pub signature_valid: bool,
+}
+
+fn decode_seeds(
+    seeds: &[String],
+    encoding: Option<&str>,
+) -> Result<Vec<Vec<u8>>, (StatusCode, Json<ErrorResponse>)> {
+    // find_program_address appends the bump, which takes one of the seed slots.
+    if seeds.len() > MAX_SEEDS - 1 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("At most {} seeds are allowed", MAX_SEEDS - 1),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let encoding = encoding.unwrap_or("utf8");
+    let mut decoded = Vec::with_capacity(seeds.len());
+    for (i, seed) in seeds.iter().enumerate() {
+        let bytes = match encoding {
+            "utf8" => seed.as_bytes().to_vec(),
+            "base58" => bs58::decode(seed).into_vec().map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        success: false,
+                        error: format!("Invalid base58 seed at index {}", i),
+                        code: ErrorCode::InvalidEncoding,
+                    }),
+                )
+            })?,
+            other => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        success: false,
+                        error: format!(
+                            "Unsupported seed_encoding '{}', expected \"utf8\" or \"base58\"",
+                            other
+                        ),
+                        code: ErrorCode::InvalidInput,
+                    }),
+                ))
+            }
+        };
+
+        if bytes.len() > MAX_SEED_LEN {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Seed at index {} is longer than {} bytes", i, MAX_SEED_LEN),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+        decoded.push(bytes);
+    }
+
+    Ok(decoded)
+}
+
+pub async fn verify_pda_linked(
+    Json(req): Json<VerifyPdaLinkedRequest>,
+) -> Result<Json<SuccessResponse<VerifyPdaLinkedResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let pda = parse_pubkey(&req.pda, "pda")?;
+    let program_id = parse_pubkey(&req.program_id, "programId")?;
+    let pubkey = parse_pubkey(&req.pubkey, "pubkey")?;
+
+    let seeds = decode_seeds(&req.seeds, None)?;
+    let seeds: Vec<&[u8]> = seeds.iter().map(|seed| seed.as_slice()).collect();
+    let pda_matches = Pubkey::try_find_program_address(&seeds, &program_id)
+        .map(|(derived, _)| derived == pda)
+        .unwrap_or(false);
+
+    let signature = decode_signature(&req.signature)?;
+    let dalek_pubkey = dalek_public_key(&pubkey)?;
+    let signature_valid = dalek_pubkey
+        .verify_strict(req.message.as_bytes(), &signature)
+        .is_ok();
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VerifyPdaLinkedResponse {
+            valid: pda_matches && signature_valid,
+            pda_matches,
+            signature_valid,
+        },
+    }))
+}
+
+//
+// /transaction/build-sponsored
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BuildSponsoredRequest {
+    pub instructions: Vec<InstructionRequest>,
+    #[serde(rename = "feePayer")]
+    pub fee_payer: String,
+    pub signers: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct BuildSponsoredResponse {
+    pub transaction: String,
+    #[serde(rename = "feePayerIndex")]
+    pub fee_payer_index: usize,
+    pub signers: Vec<String>,
+}
+
+pub async fn build_sponsored_transaction(
+    Query(options): Query<TransactionOptions>,
+    Json(req): Json<BuildSponsoredRequest>,
+) -> Result<Json<SuccessResponse<BuildSponsoredResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let fee_payer = parse_pubkey(&req.fee_payer, "feePayer")?;
+
+    let instructions = req
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(i, ix)| parse_instruction(i, ix))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let message = Message::new(&instructions, Some(&fee_payer));
+    let required_signers = &message.account_keys[..message.header.num_required_signatures as usize];
+
+    for (i, signer) in req.signers.iter().enumerate() {
+        let signer = parse_pubkey(signer, &format!("signers[{}]", i))?;
+
+        if !required_signers.contains(&signer) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Signer at index {} is not required by any instruction", i),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+    }
+
+    let fee_payer_index = required_signers
+        .iter()
+        .position(|key| *key == fee_payer)
+        .unwrap_or(0);
+    let signers = required_signers.iter().map(|key| key.to_string()).collect();
+
+    let transaction = Transaction::new_unsigned(message);
+    let serialized = bincode::serialize(&transaction).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to serialize transaction: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: BuildSponsoredResponse {
+            transaction: encode_transaction_bytes(&serialized, options.encoding.as_deref())?,
+            fee_payer_index,
+            signers,
+        },
+    }))
+}
+
+//
+// /pubkey/validate
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatePubkeyRequest {
+    pub pubkey: String,
+}
+
+#[derive(Serialize)]
+pub struct ValidatePubkeyResponse {
+    pub valid: bool,
+    pub on_curve: bool,
+}
+
+pub async fn validate_pubkey(
+    Json(req): Json<ValidatePubkeyRequest>,
+) -> Result<Json<SuccessResponse<ValidatePubkeyResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let data = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(pubkey) => ValidatePubkeyResponse {
+            valid: true,
+            on_curve: pubkey.is_on_curve(),
+        },
+        Err(_) => ValidatePubkeyResponse {
+            valid: false,
+            on_curve: false,
+        },
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /token/validate-flow
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateFlowRequest {
+    #[serde(rename = "createDecimals")]
+    pub create_decimals: u8,
+    #[serde(rename = "mintDecimals")]
+    pub mint_decimals: u8,
+}
+
+#[derive(Serialize)]
+pub struct ValidateFlowResponse {
+    pub consistent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+pub async fn validate_token_flow(
+    Json(req): Json<ValidateFlowRequest>,
+) -> Result<Json<SuccessResponse<ValidateFlowResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let consistent = req.create_decimals == req.mint_decimals;
+    let hint = (!consistent).then(|| {
+        format!(
+            "Mint was created with {} decimals but mint_to_checked uses {}; the program will reject the instruction",
+            req.create_decimals, req.mint_decimals
+        )
+    });
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: ValidateFlowResponse { consistent, hint },
+    }))
+}
+
+//
+// /account/wsol-ata
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WsolAtaRequest {
+    pub owner: String,
+}
+
+#[derive(Serialize)]
+pub struct WsolAtaResponse {
+    pub ata: String,
+    pub mint: String,
+}
+
+pub async fn wsol_ata(
+    Json(req): Json<WsolAtaRequest>,
+) -> Result<Json<SuccessResponse<WsolAtaResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let ata = get_associated_token_address(&owner, &spl_token::native_mint::ID);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: WsolAtaResponse {
+            ata: ata.to_string(),
+            mint: spl_token::native_mint::ID.to_string(),
+        },
+    }))
+}
+
+//
+// /message/sign-batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignBatchRequest {
+    pub secret: String,
+    pub messages: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct SignedMessage {
+    pub message: String,
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct SignBatchResponse {
+    pub public_key: String,
+    pub signatures: Vec<SignedMessage>,
+}
+
+pub async fn sign_batch(
+    State(state): State<AppState>,
+    Json(req): Json<SignBatchRequest>,
+) -> Result<Json<SuccessResponse<SignBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let keypair = decode_keypair(&req.secret)?;
+
+    let signatures: Vec<SignedMessage> = req
+        .messages
+        .into_iter()
+        .map(|message| SignedMessage {
+            signature: base64::encode(keypair.sign_message(message.as_bytes())),
+            message,
+        })
+        .collect();
+
+    state.stats.record_signatures(signatures.len() as u64);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SignBatchResponse {
+            public_key: keypair.pubkey().to_string(),
+            signatures,
+        },
+    }))
+}
+
+//
+// /message/verify/threshold
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdSignature {
+    pub pubkey: String,
+    pub signature: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyThresholdRequest {
+    pub message: String,
+    pub threshold: usize,
+    pub signatures: Vec<ThresholdSignature>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyThresholdResponse {
+    pub met: bool,
+    #[serde(rename = "validCount")]
+    pub valid_count: usize,
+    #[serde(rename = "validPubkeys")]
+    pub valid_pubkeys: Vec<String>,
+}
+
+pub async fn verify_threshold(
+    Json(req): Json<VerifyThresholdRequest>,
+) -> Result<Json<SuccessResponse<VerifyThresholdResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    if req.threshold == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Threshold must be at least 1".into(),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    // Malformed entries simply don't count towards the threshold.
+    let mut valid_keys: Vec<Pubkey> = Vec::new();
+    for entry in &req.signatures {
+        let Ok(pubkey) = Pubkey::from_str(entry.pubkey.trim()) else {
+            continue;
+        };
+        if valid_keys.contains(&pubkey) {
+            continue;
+        }
+        let (Ok(signature), Ok(dalek_pubkey)) =
+            (decode_signature(&entry.signature), dalek_public_key(&pubkey))
+        else {
+            continue;
+        };
+        if dalek_pubkey
+            .verify_strict(req.message.as_bytes(), &signature)
+            .is_ok()
+        {
+            valid_keys.push(pubkey);
+        }
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VerifyThresholdResponse {
+            met: valid_keys.len() >= req.threshold,
+            valid_count: valid_keys.len(),
+            valid_pubkeys: valid_keys.iter().map(|key| key.to_string()).collect(),
+        },
+    }))
+}
+
+//
+// /token/transfer
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransferTokenRequest {
+    pub source: String,
+    pub destination: String,
+    pub owner: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub amount: u64,
+}
+
+pub async fn transfer_token(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<TransferTokenRequest>,
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let source = parse_pubkey(&req.source, "source")?;
+    let destination = parse_pubkey(&req.destination, "destination")?;
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let instruction = spl_token_2022::instruction::transfer(
+        &state.token_program,
+        &source,
+        &destination,
+        &owner,
+        &[],
+        req.amount,
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Instruction error: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: instruction_output(instruction, &options)?,
+    }))
+}
+
+//
+// /token/decode-checked
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DecodeCheckedRequest {
+    pub data: String,
+}
+
+#[derive(Serialize)]
+pub struct DecodeCheckedResponse {
+    pub instruction: String,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+pub async fn decode_checked(
+    Json(req): Json<DecodeCheckedRequest>,
+) -> Result<Json<SuccessResponse<DecodeCheckedResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let data = base64::decode(&req.data).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Invalid base64 data".into(),
+                code: ErrorCode::InvalidEncoding,
+            }),
+        )
+    })?;
+
+    let instruction = TokenInstruction::unpack(&data).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to decode token instruction: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    let (name, amount, decimals) = match instruction {
+        TokenInstruction::TransferChecked { amount, decimals } => ("TransferChecked", amount, decimals),
+        TokenInstruction::ApproveChecked { amount, decimals } => ("ApproveChecked", amount, decimals),
+        TokenInstruction::MintToChecked { amount, decimals } => ("MintToChecked", amount, decimals),
+        TokenInstruction::BurnChecked { amount, decimals } => ("BurnChecked", amount, decimals),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "Data is not a checked token instruction".into(),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ))
+        }
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: DecodeCheckedResponse {
+            instruction: name.into(),
+            amount,
+            decimals,
+        },
+    }))
+}
+
+//
+// /token/prepare-create
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrepareCreateTokenRequest {
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: String,
+    pub decimals: u8,
+    /// Funds the new mint account; defaults to the mint authority.
+    pub payer: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PrepareCreateTokenResponse {
+    pub mint: KeypairResponse,
+    pub instructions: Vec<InstructionResponse>,
+}
+
+// Allocates a rent-exempt account owned by `token_program`, sized for a mint.
+fn create_mint_account(payer: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    let space = spl_token::state::Mint::LEN;
+    solana_sdk::system_instruction::create_account(
+        payer,
+        mint,
+        Rent::default().minimum_balance(space),
+        space as u64,
+        token_program,
+    )
+}
+
+pub async fn prepare_create_token(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<PrepareCreateTokenRequest>,
+) -> Result<Json<SuccessResponse<PrepareCreateTokenResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let mint_authority = parse_pubkey(&req.mint_authority, "mintAuthority")?;
+
+    let payer = match &req.payer {
+        Some(payer) => parse_pubkey(payer, "payer")?,
+        None => mint_authority,
+    };
+
+    let mint = Keypair::new();
+    let create_account = create_mint_account(&payer, &mint.pubkey(), &state.token_program);
+
+    let initialize_mint = spl_token_2022::instruction::initialize_mint2(
+        &state.token_program,
+        &mint.pubkey(),
+        &mint_authority,
+        None,
+        req.decimals,
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    state.stats.record_tokens(1);
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: PrepareCreateTokenResponse {
+            mint: KeypairResponse {
+                pubkey: mint.pubkey().to_string(),
+                secret: bs58::encode(mint.to_bytes()).into_string(),
+                secret_bytes: None,
+            },
+            instructions: instruction_list(vec![create_account, initialize_mint], &options)?,
+        },
+    }))
+}
+
+//
+// /keypair/derive-pda
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DerivePdaRequest {
+    pub program_id: String,
+    pub seeds: Vec<String>,
+    pub seed_encoding: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DerivePdaResponse {
+    pub pda: String,
+    pub bump: u8,
+}
+
+pub async fn derive_pda(
+    Json(req): Json<DerivePdaRequest>,
+) -> Result<Json<SuccessResponse<DerivePdaResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let program_id = parse_pubkey(&req.program_id, "program_id")?;
+
+    let seeds = decode_seeds(&req.seeds, req.seed_encoding.as_deref())?;
+    let seeds: Vec<&[u8]> = seeds.iter().map(|seed| seed.as_slice()).collect();
+
+    let (pda, bump) = Pubkey::try_find_program_address(&seeds, &program_id).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Unable to find a viable program address for these seeds".into(),
+                code: ErrorCode::InvalidInput,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: DerivePdaResponse {
+            pda: pda.to_string(),
+            bump,
+        },
+    }))
+}
+
+//
+// /transaction/blockhash-status
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockhashStatusRequest {
+    pub transaction: String,
+    pub encoding: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BlockhashStatusResponse {
+    pub blockhash: String,
+    pub valid: bool,
+    /// Blocks left before `lastValidBlockHeight`. This is a block-height
+    /// difference, not a slot count: skipped slots don't produce blocks, so
+    /// the wall-clock time left is usually longer than this many slots.
+    #[serde(rename = "estimatedRemainingSlots", skip_serializing_if = "Option::is_none")]
+    pub remaining_blocks: Option<u64>,
+}
+
+pub async fn blockhash_status(
+    Extension(rpc): Extension<Option<Arc<RpcClient>>>,
+    Json(req): Json<BlockhashStatusRequest>,
+) -> Result<Json<SuccessResponse<BlockhashStatusResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let rpc = rpc.ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                success: false,
+                error: "RPC is not configured; set RPC_URL to enable this endpoint".into(),
+                code: ErrorCode::RpcNotConfigured,
+            }),
+        )
+    })?;
+
+    let bytes = decode_transaction_bytes(&req.transaction, req.encoding.as_deref())?;
+    let transaction: VersionedTransaction = bincode::deserialize(&bytes).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to deserialize transaction: {}", e),
+                code: ErrorCode::InvalidTransaction,
+            }),
+        )
+    })?;
+    let blockhash = *transaction.message.recent_blockhash();
+
+    let rpc_error = |e: solana_client::client_error::ClientError| {
+        (
+            StatusCode::BAD_GATEWAY,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("RPC request failed: {}", e),
+                code: ErrorCode::RpcError,
+            }),
+        )
+    };
+
+    let commitment = CommitmentConfig::confirmed();
+    let valid = rpc
+        .is_blockhash_valid(&blockhash, commitment)
+        .await
+        .map_err(rpc_error)?;
+
+    // The RPC can only tell us the expiry of the latest blockhash, so the
+    // estimate is reported when the transaction was built against it.
+    let remaining_blocks = if valid {
+        let (latest, last_valid_height) = rpc
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map_err(rpc_error)?;
+        if latest == blockhash {
+            let height = rpc
+                .get_block_height_with_commitment(commitment)
+                .await
+                .map_err(rpc_error)?;
+            Some(last_valid_height.saturating_sub(height))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: BlockhashStatusResponse {
+            blockhash: blockhash.to_string(),
+            valid,
+            remaining_blocks,
+        },
+    }))
+}
+
+//
+// /token/set-authority/batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetAuthorityBatchRequest {
+    #[serde(rename = "currentAuthority")]
+    pub current_authority: String,
+    #[serde(rename = "newAuthority")]
+    pub new_authority: String,
+    #[serde(rename = "authorityType")]
+    pub authority_type: String,
+    pub accounts: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct SetAuthorityBatchResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+fn parse_authority_type(
+    authority_type: &str,
+) -> Result<AuthorityType, (StatusCode, Json<ErrorResponse>)> {
+    match authority_type {
+        "mint" => Ok(AuthorityType::MintTokens),
+        "freeze" => Ok(AuthorityType::FreezeAccount),
+        "owner" => Ok(AuthorityType::AccountOwner),
+        "close" => Ok(AuthorityType::CloseAccount),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Unknown authority type '{}', expected one of \"mint\", \"freeze\", \"owner\", \"close\"",
+                    other
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        )),
+    }
+}
+
+pub async fn set_authority_batch(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<SetAuthorityBatchRequest>,
+) -> Result<Json<SuccessResponse<SetAuthorityBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let authority_type = parse_authority_type(&req.authority_type)?;
+
+    let current_authority = parse_pubkey(&req.current_authority, "currentAuthority")?;
+    let new_authority = parse_pubkey(&req.new_authority, "newAuthority")?;
+
+    let mut instructions = Vec::with_capacity(req.accounts.len());
+    for (i, account) in req.accounts.iter().enumerate() {
+        let account = parse_pubkey(account, &format!("accounts[{}]", i))?;
+
+        let instruction = spl_token_2022::instruction::set_authority(
+            &state.token_program,
+            &account,
+            Some(&new_authority),
+            authority_type.clone(),
+            &current_authority,
+            &[],
+        )
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("Failed to create instruction at index {}: {}", i, e),
+                    code: ErrorCode::InstructionError,
+                }),
+            )
+        })?;
+
+        instructions.push(instruction);
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SetAuthorityBatchResponse {
+            instructions: instruction_list(instructions, &options)?,
+        },
+    }))
+}
+
+//
+// /token/set-authority
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetAuthorityRequest {
+    pub account: String,
+    pub current_authority: String,
+    // Leaving this out (or null) clears the authority for good.
+    pub new_authority: Option<String>,
+    pub authority_type: String,
+}
+
+pub async fn set_authority(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<SetAuthorityRequest>,
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let authority_type = parse_authority_type(&req.authority_type)?;
+
+    let account = parse_pubkey(&req.account, "account")?;
+    let current_authority = parse_pubkey(&req.current_authority, "current_authority")?;
+    let new_authority = req
+        .new_authority
+        .as_deref()
+        .map(|value| parse_pubkey(value, "new_authority"))
+        .transpose()?;
+
+    let instruction = spl_token_2022::instruction::set_authority(
+        &state.token_program,
+        &account,
+        new_authority.as_ref(),
+        authority_type,
+        &current_authority,
+        &[],
+    )
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to create instruction: {}", e),
+                code: ErrorCode::InstructionError,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: instruction_output(instruction, &options)?,
+    }))
+}
+
+//
+// /message/sign-timestamped
+//
+
+// Signatures older than this are rejected by /message/verify-timestamped
+// unless the caller asks for a different window.
+const DEFAULT_FRESHNESS_WINDOW_SECS: u64 = 300;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignTimestampedRequest {
+    pub secret: String,
+    pub payload: String,
+}
+
+#[derive(Serialize)]
+pub struct SignTimestampedResponse {
+    pub pubkey: String,
+    pub signature: String,
+    pub timestamp: u64,
+    #[serde(rename = "signedMessage")]
+    pub signed_message: String,
+}
+
+fn unix_timestamp() -> Result<u64, (StatusCode, Json<ErrorResponse>)> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "System clock is set before the Unix epoch".into(),
+                    code: ErrorCode::Internal,
+                }),
+            )
+        })
+}
+
+fn timestamped_message(timestamp: u64, payload: &str) -> String {
+    format!("{}|{}", timestamp, payload)
+}
+
+pub async fn sign_timestamped(
+    State(state): State<AppState>,
+    Json(req): Json<SignTimestampedRequest>,
+) -> Result<Json<SuccessResponse<SignTimestampedResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let keypair = decode_keypair(&req.secret)?;
+    let timestamp = unix_timestamp()?;
+
+    let signed_message = timestamped_message(timestamp, &req.payload);
+    let signature = keypair.sign_message(signed_message.as_bytes());
+
+    state.stats.record_signatures(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SignTimestampedResponse {
+            pubkey: keypair.pubkey().to_string(),
+            signature: base64::encode(signature),
+            timestamp,
+            signed_message,
+        },
+    }))
+}
+
+//
+// /message/verify-timestamped
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyTimestampedRequest {
+    pub pubkey: String,
+    pub signature: String,
+    pub timestamp: u64,
+    pub payload: String,
+    #[serde(rename = "maxAgeSecs")]
+    pub max_age_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyTimestampedResponse {
+    pub valid: bool,
+    #[serde(rename = "signatureValid")]
+    pub signature_valid: bool,
+    pub fresh: bool,
+    #[serde(rename = "ageSecs")]
+    pub age_secs: u64,
+}
+
+pub async fn verify_timestamped(
+    Json(req): Json<VerifyTimestampedRequest>,
+) -> Result<Json<SuccessResponse<VerifyTimestampedResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let pubkey = parse_pubkey(&req.pubkey, "pubkey")?;
+    let signature = decode_signature(&req.signature)?;
+    let dalek_pubkey = dalek_public_key(&pubkey)?;
+
+    let signed_message = timestamped_message(req.timestamp, &req.payload);
+    let signature_valid = dalek_pubkey
+        .verify_strict(signed_message.as_bytes(), &signature)
+        .is_ok();
+
+    // Clock skew cuts both ways, so a timestamp slightly in the future is
+    // held to the same window as one in the past.
+    let age_secs = unix_timestamp()?.abs_diff(req.timestamp);
+    let fresh = age_secs <= req.max_age_secs.unwrap_or(DEFAULT_FRESHNESS_WINDOW_SECS);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VerifyTimestampedResponse {
+            valid: signature_valid && fresh,
+            signature_valid,
+            fresh,
+            age_secs,
+        },
+    }))
+}
+
+//
+// /signature/validate
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateSignatureRequest {
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct ValidateSignatureResponse {
+    pub valid: bool,
+    // Decoded byte length, or null when the input isn't base58 at all.
+    pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+pub async fn validate_signature(
+    Json(req): Json<ValidateSignatureRequest>,
+) -> Result<Json<SuccessResponse<ValidateSignatureResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let data = match bs58::decode(req.signature.trim()).into_vec() {
+        Ok(bytes) if bytes.len() == 64 => ValidateSignatureResponse {
+            valid: true,
+            length: Some(bytes.len()),
+            reason: None,
+        },
+        Ok(bytes) => ValidateSignatureResponse {
+            valid: false,
+            length: Some(bytes.len()),
+            reason: Some("wrong_length"),
+        },
+        Err(_) => ValidateSignatureResponse {
+            valid: false,
+            length: None,
+            reason: Some("not_base58"),
+        },
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /token/amount/convert
+//
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvertDirection {
+    #[default]
+    ToRaw,
+    ToUi,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConvertAmountRequest {
+    pub ui_amount: Option<f64>,
+    pub raw: Option<u64>,
+    pub decimals: u8,
+    #[serde(default)]
+    pub direction: ConvertDirection,
+}
+
+#[derive(Serialize)]
+pub struct ConvertAmountResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ui_amount: Option<f64>,
+    /// The exact decimal value; `ui_amount` loses precision once the raw
+    /// amount exceeds 2^53.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ui_amount_string: Option<String>,
+}
+
+fn invalid_amount(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            success: false,
+            error,
+            code: ErrorCode::InvalidAmount,
+        }),
+    )
+}
+
+fn arithmetic_overflow(detail: impl fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    invalid_amount(format!("Arithmetic overflow: {}", detail))
+}
+
+fn missing_field(field: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            success: false,
+            error: format!("Missing required field: {}", field),
+            code: ErrorCode::MissingField,
+        }),
+    )
+}
+
+pub async fn ui_to_raw(
+    Json(req): Json<ConvertAmountRequest>,
+) -> Result<Json<SuccessResponse<ConvertAmountResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let scale = 10f64.powi(i32::from(req.decimals));
+
+    let data = match req.direction {
+        ConvertDirection::ToRaw => {
+            let ui_amount = req.ui_amount.ok_or_else(|| missing_field("ui_amount"))?;
+            if !ui_amount.is_finite() || ui_amount < 0.0 {
+                return Err(invalid_amount(format!(
+                    "ui_amount must be a non-negative number, got {}",
+                    ui_amount
+                )));
+            }
+
+            // u64::MAX rounds up to 2^64 as an f64, so anything at or above
+            // that cannot be represented.
+            let scaled = (ui_amount * scale).round();
+            if scaled >= u64::MAX as f64 {
+                return Err(arithmetic_overflow(format!(
+                    "{} with {} decimals overflows a u64 raw amount",
+                    ui_amount, req.decimals
+                )));
+            }
+
+            ConvertAmountResponse {
+                raw: Some(scaled as u64),
+                ui_amount: None,
+                ui_amount_string: None,
+            }
+        }
+        ConvertDirection::ToUi => {
+            let raw = req.raw.ok_or_else(|| missing_field("raw"))?;
+            let ui_amount_string =
+                spl_token::amount_to_ui_amount_string_trimmed(raw, req.decimals);
+            ConvertAmountResponse {
+                raw: None,
+                ui_amount: ui_amount_string.parse().ok(),
+                ui_amount_string: Some(ui_amount_string),
+            }
+        }
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /transaction/from-responses
+//
+
+// Older clients still hold responses with the `{ pubkey, isSigner }` account
+// shape, so both spellings are accepted and a missing writable flag is
+// treated as writable: over-locking an account is harmless, under-locking
+// makes the transaction fail. Unlike other request types these tolerate
+// unknown fields, since responses carry extras such as `mint_space`.
+#[derive(Deserialize)]
+pub struct ResponseAccountMeta {
+    pub pubkey: String,
+    #[serde(alias = "isSigner")]
+    pub is_signer: bool,
+    #[serde(alias = "isWritable")]
+    pub is_writable: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct ResponseInstruction {
+    pub program_id: String,
+    pub accounts: Vec<ResponseAccountMeta>,
+    pub instruction_data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FromResponsesRequest {
+    #[serde(rename = "feePayer")]
+    pub fee_payer: String,
+    #[serde(rename = "recentBlockhash")]
+    pub recent_blockhash: Option<String>,
+    pub responses: Vec<ResponseInstruction>,
+}
+
+#[derive(Serialize)]
+pub struct FromResponsesResponse {
+    pub transaction: String,
+}
+
+fn parse_response_instruction(
+    index: usize,
+    response: &ResponseInstruction,
+) -> Result<Instruction, (StatusCode, Json<ErrorResponse>)> {
+    let program_id = parse_pubkey(
+        &response.program_id,
+        &format!("responses[{}].program_id", index),
+    )?;
+
+    let mut accounts = Vec::with_capacity(response.accounts.len());
+    for (j, meta) in response.accounts.iter().enumerate() {
+        let pubkey = parse_pubkey(
+            &meta.pubkey,
+            &format!("responses[{}].accounts[{}].pubkey", index, j),
+        )?;
+        accounts.push(AccountMeta {
+            pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable.unwrap_or(true),
+        });
+    }
+
+    let data = base64::decode(&response.instruction_data).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Invalid base64 instruction_data in response {}", index),
+                code: ErrorCode::InvalidEncoding,
+            }),
+        )
+    })?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+pub async fn transaction_from_responses(
+    Query(options): Query<TransactionOptions>,
+    Json(req): Json<FromResponsesRequest>,
+) -> Result<Json<SuccessResponse<FromResponsesResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let fee_payer = parse_pubkey(&req.fee_payer, "feePayer")?;
+
+    // A zero blockhash would build fine and only fail once submitted, so an
+    // omitted one is rejected up front.
+    let blockhash = req
+        .recent_blockhash
+        .as_deref()
+        .ok_or_else(|| missing_field("recentBlockhash"))?;
+    let recent_blockhash = Hash::from_str(blockhash.trim()).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "Invalid recent blockhash".into(),
+                code: ErrorCode::InvalidBlockhash,
+            }),
+        )
+    })?;
+
+    if req.responses.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "At least one response is required".into(),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let instructions = req
+        .responses
+        .iter()
+        .enumerate()
+        .map(|(i, response)| parse_response_instruction(i, response))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer), &recent_blockhash);
+    let transaction = Transaction::new_unsigned(message);
+    let serialized = bincode::serialize(&transaction).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to serialize transaction: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: FromResponsesResponse {
+            transaction: encode_transaction_bytes(&serialized, options.encoding.as_deref())?,
+        },
+    }))
+}
+
+//
+// /metrics
+//
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+//
+// /message/verify-batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyBatchItem {
+    pub message: String,
+    pub signature: String,
+    pub pubkey: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyBatchRequest {
+    pub items: Vec<VerifyBatchItem>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyBatchResult {
+    pub valid: bool,
+    pub pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyBatchResponse {
+    pub results: Vec<VerifyBatchResult>,
+}
+
+fn verify_item(item: &VerifyBatchItem) -> Result<bool, (StatusCode, Json<ErrorResponse>)> {
+    let pubkey = parse_pubkey(&item.pubkey, "pubkey")?;
+    let signature = decode_signature(&item.signature)?;
+    let dalek_pubkey = dalek_public_key(&pubkey)?;
+
+    Ok(dalek_pubkey
+        .verify_strict(item.message.as_bytes(), &signature)
+        .is_ok())
+}
+
+pub async fn verify_batch(
+    Json(req): Json<VerifyBatchRequest>,
+) -> Result<Json<SuccessResponse<VerifyBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    // A malformed item is reported in its own slot instead of failing the
+    // whole batch, so results always line up with the request.
+    let results = req
+        .items
+        .iter()
+        .map(|item| match verify_item(item) {
+            Ok(valid) => VerifyBatchResult {
+                valid,
+                pubkey: item.pubkey.clone(),
+                error: None,
+            },
+            Err((_, Json(error))) => VerifyBatchResult {
+                valid: false,
+                pubkey: item.pubkey.clone(),
+                error: Some(error.error),
+            },
+        })
+        .collect();
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VerifyBatchResponse { results },
+    }))
+}
+
+//
+// /keypair/pubkey
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecoverPubkeyRequest {
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+pub struct RecoverPubkeyResponse {
+    pub pubkey: String,
+}
+
+pub async fn recover_pubkey(
+    Json(req): Json<RecoverPubkeyRequest>,
+) -> Result<Json<SuccessResponse<RecoverPubkeyResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let keypair = decode_keypair(&req.secret)?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: RecoverPubkeyResponse {
+            pubkey: keypair.pubkey().to_string(),
+        },
+    }))
+}
+
+//
+// /token/create/extensions
+//
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MintExtensionRequest {
+    /// Both authorities default to the mint authority when omitted.
+    TransferFee {
+        basis_points: u16,
+        max_fee: u64,
+        config_authority: Option<String>,
+        withdraw_authority: Option<String>,
+    },
+    NonTransferable,
+    MintCloseAuthority {
+        close_authority: String,
+    },
+    PermanentDelegate {
+        delegate: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateTokenExtensionsRequest {
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: String,
+    pub mint: String,
+    pub decimals: u8,
+    #[serde(rename = "freezeAuthority")]
+    pub freeze_authority: Option<String>,
+    pub extensions: Vec<MintExtensionRequest>,
+}
+
+#[derive(Serialize)]
+pub struct CreateTokenExtensionsResponse {
+    pub instructions: Vec<InstructionResponse>,
+    /// Mint account size including the extensions, for the `create_account`
+    /// that must precede these instructions.
+    pub mint_space: usize,
+    pub rent_exempt_lamports: u64,
+}
+
+fn instruction_error(e: impl fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            success: false,
+            error: format!("Failed to create instruction: {}", e),
+            code: ErrorCode::InstructionError,
+        }),
+    )
+}
+
+pub async fn create_token_with_extensions(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<CreateTokenExtensionsRequest>,
+) -> Result<Json<SuccessResponse<CreateTokenExtensionsResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    use spl_token_2022::extension::{
+        ExtensionType,
+        transfer_fee::{MAX_FEE_BASIS_POINTS, instruction::initialize_transfer_fee_config},
+    };
+
+    let program_id = spl_token_2022::ID;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let mint_authority = parse_pubkey(&req.mint_authority, "mintAuthority")?;
+    let freeze_authority = req
+        .freeze_authority
+        .as_deref()
+        .map(|value| parse_pubkey(value, "freezeAuthority"))
+        .transpose()?;
+
+    let mut extension_types = Vec::with_capacity(req.extensions.len());
+    // Extensions must be initialized before the mint itself.
+    let mut instructions = Vec::with_capacity(req.extensions.len() + 1);
+
+    for (i, extension) in req.extensions.iter().enumerate() {
+        let (extension_type, instruction) = match extension {
+            MintExtensionRequest::TransferFee {
+                basis_points,
+                max_fee,
+                config_authority,
+                withdraw_authority,
+            } => {
+                if *basis_points > MAX_FEE_BASIS_POINTS {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse {
+                            success: false,
+                            error: format!(
+                                "extensions[{}].basis_points must be at most {}, got {}",
+                                i, MAX_FEE_BASIS_POINTS, basis_points
+                            ),
+                            code: ErrorCode::InvalidInput,
+                        }),
+                    ));
+                }
+                let config_authority = match config_authority {
+                    Some(value) => {
+                        parse_pubkey(value, &format!("extensions[{}].config_authority", i))?
+                    }
+                    None => mint_authority,
+                };
+                let withdraw_authority = match withdraw_authority {
+                    Some(value) => {
+                        parse_pubkey(value, &format!("extensions[{}].withdraw_authority", i))?
+                    }
+                    None => mint_authority,
+                };
+                (
+                    ExtensionType::TransferFeeConfig,
+                    initialize_transfer_fee_config(
+                        &program_id,
+                        &mint,
+                        Some(&config_authority),
+                        Some(&withdraw_authority),
+                        *basis_points,
+                        *max_fee,
+                    ),
+                )
+            }
+            MintExtensionRequest::NonTransferable => (
+                ExtensionType::NonTransferable,
+                spl_token_2022::instruction::initialize_non_transferable_mint(&program_id, &mint),
+            ),
+            MintExtensionRequest::MintCloseAuthority { close_authority } => {
+                let close_authority =
+                    parse_pubkey(close_authority, &format!("extensions[{}].close_authority", i))?;
+                (
+                    ExtensionType::MintCloseAuthority,
+                    spl_token_2022::instruction::initialize_mint_close_authority(
+                        &program_id,
+                        &mint,
+                        Some(&close_authority),
+                    ),
+                )
+            }
+            MintExtensionRequest::PermanentDelegate { delegate } => {
+                let delegate = parse_pubkey(delegate, &format!("extensions[{}].delegate", i))?;
+                (
+                    ExtensionType::PermanentDelegate,
+                    spl_token_2022::instruction::initialize_permanent_delegate(
+                        &program_id,
+                        &mint,
+                        &delegate,
+                    ),
+                )
+            }
+        };
+
+        if extension_types.contains(&extension_type) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!("extensions[{}] repeats an extension already listed", i),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+        extension_types.push(extension_type);
+        instructions.push(instruction.map_err(instruction_error)?);
+    }
+
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &program_id,
+            &mint,
+            &mint_authority,
+            freeze_authority.as_ref(),
+            req.decimals,
+        )
+        .map_err(instruction_error)?,
+    );
+
+    let mint_space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extension_types)
+            .map_err(instruction_error)?;
+
+    state.stats.record_tokens(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: CreateTokenExtensionsResponse {
+            instructions: instruction_list(instructions, &options)?,
+            mint_space,
+            rent_exempt_lamports: Rent::default().minimum_balance(mint_space),
+        },
+    }))
+}
+
+//
+// /send/sol/memo
+//
+
+// Beyond this a memo plus a transfer no longer fits comfortably in a
+// single transaction alongside signatures and account keys.
+const MAX_MEMO_BYTES: usize = 566;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SendSolMemoRequest {
+    pub from: String,
+    pub to: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub lamports: u64,
+    pub memo: String,
+}
+
+#[derive(Serialize)]
+pub struct SendSolMemoResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn send_sol_with_memo(
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<SendSolMemoRequest>,
+) -> Result<Json<SuccessResponse<SendSolMemoResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let from = parse_pubkey(&req.from, "from")?;
+    let to = parse_pubkey(&req.to, "to")?;
+
+    if req.memo.is_empty() {
+        return Err(missing_field("memo"));
+    }
+    if req.memo.len() > MAX_MEMO_BYTES {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Memo is {} bytes, the limit is {}",
+                    req.memo.len(),
+                    MAX_MEMO_BYTES
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let transfer = solana_sdk::system_instruction::transfer(&from, &to, req.lamports);
+    let memo = spl_memo::build_memo(req.memo.as_bytes(), &[&from]);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SendSolMemoResponse {
+            instructions: instruction_list(vec![transfer, memo], &options)?,
+        },
+    }))
+}
+
+//
+// /transaction/decode
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DecodeTransactionRequest {
+    /// A serialized transaction; mutually exclusive with `message`.
+    pub transaction: Option<String>,
+    pub message: Option<String>,
+    pub encoding: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaResponse>,
+    pub data: String,
+}
+
+#[derive(Serialize)]
+pub struct DecodeTransactionResponse {
+    /// "legacy" or the numeric message version.
+    pub version: serde_json::Value,
+    pub fee_payer: String,
+    pub recent_blockhash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<String>>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+fn invalid_transaction(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            success: false,
+            error,
+            code: ErrorCode::InvalidTransaction,
+        }),
+    )
+}
+
+pub async fn decode_transaction(
+    Json(req): Json<DecodeTransactionRequest>,
+) -> Result<Json<SuccessResponse<DecodeTransactionResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    use solana_sdk::message::VersionedMessage;
+
+    let encoding = req.encoding.as_deref();
+    let (message, signatures) = match (&req.transaction, &req.message) {
+        (Some(transaction), None) => {
+            let bytes = decode_transaction_bytes(transaction, encoding)?;
+            let transaction: VersionedTransaction = bincode::deserialize(&bytes).map_err(|e| {
+                invalid_transaction(format!("Failed to deserialize transaction: {}", e))
+            })?;
+            let signatures = transaction.signatures.iter().map(ToString::to_string).collect();
+            (transaction.message, Some(signatures))
+        }
+        (None, Some(message)) => {
+            let bytes = decode_transaction_bytes(message, encoding)?;
+            let message: VersionedMessage = bincode::deserialize(&bytes).map_err(|e| {
+                invalid_transaction(format!("Failed to deserialize message: {}", e))
+            })?;
+            (message, None)
+        }
+        (Some(_), Some(_)) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: "Provide either transaction or message, not both".into(),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ))
+        }
+        (None, None) => return Err(missing_field("transaction or message")),
+    };
+
+    // Keys loaded from lookup tables can only be resolved against the chain.
+    if message
+        .address_table_lookups()
+        .is_some_and(|lookups| !lookups.is_empty())
+    {
+        return Err(invalid_transaction(
+            "Messages using address lookup tables cannot be decoded offline".into(),
+        ));
+    }
+
+    let keys = message.static_account_keys();
+    let Some(fee_payer) = keys.first() else {
+        return Err(invalid_transaction("Message has no account keys".into()));
+    };
+    let key_at = |index: u8| {
+        keys.get(usize::from(index)).ok_or_else(|| {
+            invalid_transaction(format!("Account index {} is out of range", index))
+        })
+    };
+
+    let mut instructions = Vec::with_capacity(message.instructions().len());
+    for compiled in message.instructions() {
+        let program_id = key_at(compiled.program_id_index)?;
+        let mut accounts = Vec::with_capacity(compiled.accounts.len());
+        for &index in &compiled.accounts {
+            accounts.push(AccountMetaResponse {
+                pubkey: key_at(index)?.to_string(),
+                is_signer: message.is_signer(usize::from(index)),
+                is_writable: message.is_maybe_writable(usize::from(index)),
+            });
+        }
+        instructions.push(DecodedInstruction {
+            program_id: program_id.to_string(),
+            accounts,
+            data: base64::encode(&compiled.data),
+        });
+    }
+
+    let version = match &message {
+        VersionedMessage::Legacy(_) => serde_json::Value::from("legacy"),
+        VersionedMessage::V0(_) => serde_json::Value::from(0),
+    };
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: DecodeTransactionResponse {
+            version,
+            fee_payer: fee_payer.to_string(),
+            recent_blockhash: message.recent_blockhash().to_string(),
+            signatures,
+            instructions,
+        },
+    }))
+}
+
+//
+// /keypair/mnemonic
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateMnemonicRequest {
+    pub words: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct MnemonicKeypairResponse {
+    pub mnemonic: String,
+    pub pubkey: String,
+    pub secret: String,
+}
+
+// Without an explicit path this derives the first account at
+// m/44'/501'/0'/0', the path Phantom and solana-keygen use for a seed phrase.
+fn derive_mnemonic_keypair(
+    mnemonic: &bip39::Mnemonic,
+    passphrase: &str,
+    path: Option<DerivationPath>,
+) -> Result<Keypair, (StatusCode, Json<ErrorResponse>)> {
+    let seed = mnemonic.to_seed(passphrase);
+    let path = path.unwrap_or_else(|| DerivationPath::new_bip44(Some(0), Some(0)));
+    keypair_from_seed_and_derivation_path(&seed, Some(path)).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to derive keypair: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })
+}
+
+pub async fn generate_mnemonic(
+    State(state): State<AppState>,
+    Json(req): Json<GenerateMnemonicRequest>,
+) -> Result<Json<SuccessResponse<MnemonicKeypairResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let words = req.words.unwrap_or(12);
+    if words != 12 && words != 24 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("words must be 12 or 24, got {}", words),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let mnemonic = bip39::Mnemonic::generate(words).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Failed to generate mnemonic: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })?;
+    let keypair = derive_mnemonic_keypair(&mnemonic, "", None)?;
+
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: MnemonicKeypairResponse {
+            mnemonic: mnemonic.to_string(),
+            pubkey: keypair.pubkey().to_string(),
+            secret: bs58::encode(keypair.to_bytes()).into_string(),
+        },
+    }))
+}
+
+//
+// /keypair/from-mnemonic
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FromMnemonicRequest {
+    pub mnemonic: String,
+    pub passphrase: Option<String>,
+    pub derivation_path: Option<String>,
+}
+
+pub async fn keypair_from_mnemonic(
+    State(state): State<AppState>,
+    Json(req): Json<FromMnemonicRequest>,
+) -> Result<Json<SuccessResponse<KeypairResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let phrase = require_field(&req.mnemonic, "mnemonic")?
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &phrase)
+        .map_err(|e| {
+            let error = match e {
+                bip39::Error::BadWordCount(count) => format!(
+                    "Mnemonic has {} words, expected 12, 15, 18, 21 or 24",
+                    count
+                ),
+                bip39::Error::InvalidChecksum => {
+                    "Mnemonic checksum is invalid; check the words and their order".to_string()
+                }
+                other => format!("Invalid mnemonic: {}", other),
+            };
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error,
+                    code: ErrorCode::InvalidInput,
+                }),
+            )
+        })?;
+
+    let path = req
+        .derivation_path
+        .as_deref()
+        .map(|path| {
+            DerivationPath::from_absolute_path_str(path.trim()).map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        success: false,
+                        error: format!("Invalid derivation_path: {}", e),
+                        code: ErrorCode::InvalidInput,
+                    }),
+                )
+            })
+        })
+        .transpose()?;
+
+    let keypair =
+        derive_mnemonic_keypair(&mnemonic, req.passphrase.as_deref().unwrap_or(""), path)?;
+
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: KeypairResponse {
+            pubkey: keypair.pubkey().to_string(),
+            secret: bs58::encode(keypair.to_bytes()).into_string(),
+            secret_bytes: None,
+        },
+    }))
+}
+
+//
+// /fees/estimate
+//
+
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+// Compute units the runtime grants each instruction when the transaction
+// doesn't set a limit, and the per-transaction cap on that total.
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeesEstimateRequest {
+    pub instruction_count: u64,
+    pub signature_count: u64,
+    /// Priority fee in micro-lamports per compute unit.
+    pub compute_unit_price: Option<u64>,
+    pub compute_unit_limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct FeesBreakdown {
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub compute_unit_limit: u64,
+    pub compute_unit_price: u64,
+}
+
+#[derive(Serialize)]
+pub struct FeesEstimateResponse {
+    pub lamports: u64,
+    pub breakdown: FeesBreakdown,
+}
+
+pub async fn fees_estimate(
+    Json(req): Json<FeesEstimateRequest>,
+) -> Result<Json<SuccessResponse<FeesEstimateResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    if req.signature_count == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: "signature_count must be at least 1 (the fee payer)".into(),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let overflow = || arithmetic_overflow("fee estimate exceeds a u64");
+
+    let base_fee = req
+        .signature_count
+        .checked_mul(LAMPORTS_PER_SIGNATURE)
+        .ok_or_else(overflow)?;
+
+    let compute_unit_limit = match req.compute_unit_limit {
+        Some(limit) => limit.min(MAX_COMPUTE_UNIT_LIMIT),
+        None => req
+            .instruction_count
+            .saturating_mul(DEFAULT_INSTRUCTION_COMPUTE_UNITS)
+            .min(MAX_COMPUTE_UNIT_LIMIT),
+    };
+    let compute_unit_price = req.compute_unit_price.unwrap_or(0);
+
+    // The runtime rounds the priority fee up to a whole lamport.
+    let micro_lamports = u128::from(compute_unit_price) * u128::from(compute_unit_limit);
+    let priority_fee = u64::try_from(micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT))
+        .map_err(|_| overflow())?;
+
+    let lamports = base_fee.checked_add(priority_fee).ok_or_else(overflow)?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: FeesEstimateResponse {
+            lamports,
+            breakdown: FeesBreakdown {
+                base_fee,
+                priority_fee,
+                compute_unit_limit,
+                compute_unit_price,
+            },
+        },
+    }))
+}
+
+//
+// /instructions/compute-budget
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComputeBudgetRequest {
+    pub compute_unit_limit: Option<u32>,
+    /// Micro-lamports per compute unit.
+    pub compute_unit_price: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ComputeBudgetResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn compute_budget(
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<ComputeBudgetRequest>,
+) -> Result<Json<SuccessResponse<ComputeBudgetResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    if req.compute_unit_limit.is_none() && req.compute_unit_price.is_none() {
+        return Err(missing_field("compute_unit_limit or compute_unit_price"));
+    }
+
+    let mut instructions = Vec::with_capacity(2);
+    if let Some(limit) = req.compute_unit_limit {
+        if u64::from(limit) > MAX_COMPUTE_UNIT_LIMIT {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "compute_unit_limit must be at most {}, got {}",
+                        MAX_COMPUTE_UNIT_LIMIT, limit
+                    ),
+                    code: ErrorCode::InvalidInput,
+                }),
+            ));
+        }
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    if let Some(price) = req.compute_unit_price {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: ComputeBudgetResponse {
+            instructions: instruction_list(instructions, &options)?,
+        },
+    }))
+}
+
+//
+// /token/create/new-mint
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateNewMintRequest {
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: String,
+    pub decimals: u8,
+    /// Funds the new mint account; defaults to the mint authority.
+    pub payer: Option<String>,
+    #[serde(rename = "freezeAuthority")]
+    pub freeze_authority: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CreateNewMintResponse {
+    pub pubkey: String,
+    pub secret: String,
+    pub instructions: Vec<InstructionResponse>,
+}
+
+pub async fn create_token_new_mint(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<CreateNewMintRequest>,
+) -> Result<Json<SuccessResponse<CreateNewMintResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let mint_authority = parse_pubkey(&req.mint_authority, "mintAuthority")?;
+    let payer = match &req.payer {
+        Some(payer) => parse_pubkey(payer, "payer")?,
+        None => mint_authority,
+    };
+    let freeze_authority = req
+        .freeze_authority
+        .as_deref()
+        .map(|value| parse_pubkey(value, "freezeAuthority"))
+        .transpose()?;
+
+    let mint = Keypair::new();
+    let create_account = create_mint_account(&payer, &mint.pubkey(), &state.token_program);
+    let initialize_mint = spl_token_2022::instruction::initialize_mint(
+        &state.token_program,
+        &mint.pubkey(),
+        &mint_authority,
+        freeze_authority.as_ref(),
+        req.decimals,
+    )
+    .map_err(instruction_error)?;
+
+    state.stats.record_tokens(1);
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: CreateNewMintResponse {
+            pubkey: mint.pubkey().to_string(),
+            secret: bs58::encode(mint.to_bytes()).into_string(),
+            instructions: instruction_list(vec![create_account, initialize_mint], &options)?,
+        },
+    }))
+}
+
+//
+// /version
+//
+
+#[derive(Serialize)]
+pub struct VersionResponse {
+    pub version: &'static str,
+    pub git_sha: Option<&'static str>,
+    pub build_time: Option<&'static str>,
+}
+
+pub async fn version() -> Json<SuccessResponse<VersionResponse>> {
+    Json(SuccessResponse {
+        success: true,
+        data: VersionResponse {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: option_env!("GIT_SHA"),
+            build_time: option_env!("BUILD_TIME"),
+        },
+    })
+}
+
+//
+// /send/token/batch
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBatchEntry {
+    /// Recipient wallet; as with `/send/token`, the tokens land in its
+    /// associated token account.
+    pub destination: String,
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub amount: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SendTokenBatchRequest {
+    pub owner: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub entries: Vec<TokenBatchEntry>,
+}
+
+/// A built entry has the same fields as a `/send/token` response; a
+/// rejected one only echoes `destination` next to the `error`.
+#[derive(Serialize)]
+pub struct TokenBatchResult {
+    pub destination: String,
+    #[serde(flatten)]
+    pub transfer: Option<SendTokenResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SendTokenBatchResponse {
+    pub results: Vec<TokenBatchResult>,
+}
+
+pub async fn send_token_batch(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<SendTokenBatchRequest>,
+) -> Result<Json<SuccessResponse<SendTokenBatchResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let owner = parse_pubkey(&req.owner, "owner")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let source =
+        get_associated_token_address_with_program_id(&owner, &mint, &state.token_program);
+
+    let build = |i: usize, entry: &TokenBatchEntry| {
+        let destination = parse_pubkey(&entry.destination, &format!("entries[{}].destination", i))?;
+        if entry.amount == 0 {
+            return Err(invalid_amount(format!("entries[{}].amount must be greater than zero", i)));
+        }
+        let destination_ata =
+            get_associated_token_address_with_program_id(&destination, &mint, &state.token_program);
+        let instruction = spl_token_2022::instruction::transfer_checked(
+            &state.token_program,
+            &source,
+            &mint,
+            &destination_ata,
+            &owner,
+            &[],
+            entry.amount,
+            req.decimals,
+        )
+        .map_err(instruction_error)?;
+        Ok((instruction, destination_ata))
+    };
+
+    // Bad entries are reported in place so one typo doesn't sink an airdrop.
+    // A bad query option applies to every entry, so it fails the request.
+    let mut results = Vec::with_capacity(req.entries.len());
+    for (i, entry) in req.entries.iter().enumerate() {
+        results.push(match build(i, entry) {
+            Ok((instruction, destination_ata)) => TokenBatchResult {
+                destination: entry.destination.clone(),
+                transfer: Some(SendTokenResponse {
+                    instruction: InstructionResponse::with_options(instruction, &options)?,
+                    source: source.to_string(),
+                    destination_ata: destination_ata.to_string(),
+                }),
+                error: None,
+            },
+            Err((_, Json(error))) => TokenBatchResult {
+                destination: entry.destination.clone(),
+                transfer: None,
+                error: Some(error.error),
+            },
+        });
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SendTokenBatchResponse { results },
+    }))
+}
+
+//
+// /token/multisig/create
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateMultisigRequest {
+    pub multisig: String,
+    pub signers: Vec<String>,
+    pub m: u8,
+}
+
+#[derive(Serialize)]
+pub struct CreateMultisigResponse {
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    /// Size of the multisig account, for the `create_account` that must
+    /// precede this instruction.
+    pub multisig_space: usize,
+    pub rent_exempt_lamports: u64,
+}
+
+pub async fn create_multisig(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<CreateMultisigRequest>,
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<CreateMultisigResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    use spl_token::{instruction::MAX_SIGNERS, state::Multisig};
+
+    let multisig = parse_pubkey(&req.multisig, "multisig")?;
+    let signers = req
+        .signers
+        .iter()
+        .enumerate()
+        .map(|(i, signer)| parse_pubkey(signer, &format!("signers[{}]", i)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let invalid = |error: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error,
+                code: ErrorCode::InvalidInput,
+            }),
+        )
+    };
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(invalid(format!(
+            "signers must list between 1 and {} pubkeys, got {}",
+            MAX_SIGNERS,
+            signers.len()
+        )));
+    }
+    if req.m == 0 || usize::from(req.m) > signers.len() {
+        return Err(invalid(format!(
+            "m must be between 1 and the number of signers ({}), got {}",
+            signers.len(),
+            req.m
+        )));
+    }
+
+    let signer_refs: Vec<&Pubkey> = signers.iter().collect();
+    let instruction = spl_token_2022::instruction::initialize_multisig(
+        &state.token_program,
+        &multisig,
+        &signer_refs,
+        req.m,
+    )
+    .map_err(instruction_error)?;
+
+    let data = instruction_output_with(instruction, &options, |instruction| {
+        CreateMultisigResponse {
+            instruction,
+            multisig_space: Multisig::LEN,
+            rent_exempt_lamports: Rent::default().minimum_balance(Multisig::LEN),
+        }
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /blockhash/validate
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateBlockhashRequest {
+    pub blockhash: String,
+}
+
+#[derive(Serialize)]
+pub struct ValidateBlockhashResponse {
+    pub valid: bool,
+}
+
+pub async fn validate_blockhash(
+    Json(req): Json<ValidateBlockhashRequest>,
+) -> Result<Json<SuccessResponse<ValidateBlockhashResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let valid = Hash::from_str(req.blockhash.trim()).is_ok();
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: ValidateBlockhashResponse { valid },
+    }))
+}
+
+//
+// /keypair/vanity
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VanityKeypairRequest {
+    pub prefix: String,
+}
+
+#[derive(Serialize)]
+pub struct VanityKeypairResponse {
+    pub pubkey: String,
+    pub secret: String,
+    pub attempts: u64,
+}
+
+// Each extra character multiplies the expected search by 58, so anything
+// past four would routinely blow through the limits below.
+const MAX_VANITY_PREFIX_LEN: usize = 4;
+const MAX_VANITY_ATTEMPTS: u64 = 20_000_000;
+const VANITY_TIME_LIMIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+pub async fn vanity_keypair(
+    State(state): State<AppState>,
+    Json(req): Json<VanityKeypairRequest>,
+) -> Result<Json<SuccessResponse<VanityKeypairResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let prefix = require_field(&req.prefix, "prefix")?.to_string();
+    if prefix.chars().count() > MAX_VANITY_PREFIX_LEN {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Prefix must be at most {} characters, got {}",
+                    MAX_VANITY_PREFIX_LEN,
+                    prefix.chars().count()
+                ),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+    if let Some(c) = prefix.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Prefix contains '{}', which never appears in base58", c),
+                code: ErrorCode::InvalidInput,
+            }),
+        ));
+    }
+
+    let found = tokio::task::spawn_blocking(move || {
+        let start = std::time::Instant::now();
+        for attempts in 1..=MAX_VANITY_ATTEMPTS {
+            let keypair = Keypair::new();
+            let pubkey = keypair.pubkey().to_string();
+            if pubkey.starts_with(&prefix) {
+                return Some((keypair, pubkey, attempts));
+            }
+            if attempts % 1024 == 0 && start.elapsed() >= VANITY_TIME_LIMIT {
+                break;
+            }
+        }
+        None
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Vanity search failed: {}", e),
+                code: ErrorCode::Internal,
+            }),
+        )
+    })?;
+
+    let Some((keypair, pubkey, attempts)) = found else {
+        return Err((
+            StatusCode::REQUEST_TIMEOUT,
+            Json(ErrorResponse {
+                success: false,
+                error: "No matching keypair found within the search limit, try a shorter prefix"
+                    .into(),
+                code: ErrorCode::RequestTimeout,
+            }),
+        ));
+    };
+
+    state.stats.record_keypairs(1);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: VanityKeypairResponse {
+            pubkey,
+            secret: bs58::encode(keypair.to_bytes()).into_string(),
+            attempts,
+        },
+    }))
+}
+
+//
+// /send/sol/drain
+//
+
+// One signature at the default 5000 lamports per signature.
+const DEFAULT_DRAIN_FEE_RESERVE: u64 = 5000;
+
+fn default_drain_fee_reserve() -> u64 {
+    DEFAULT_DRAIN_FEE_RESERVE
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DrainSolRequest {
+    pub from: String,
+    pub to: String,
+    /// The sender's current balance; the server has no RPC view of it.
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub balance: u64,
+    #[serde(
+        default = "default_drain_fee_reserve",
+        deserialize_with = "u64_from_str_or_number"
+    )]
+    pub fee_reserve: u64,
+}
+
+pub async fn drain_sol(
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<DrainSolRequest>,
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let from = parse_pubkey(&req.from, "from")?;
+    let to = parse_pubkey(&req.to, "to")?;
+
+    let lamports = req
+        .balance
+        .checked_sub(req.fee_reserve)
+        .filter(|lamports| *lamports > 0)
+        .ok_or_else(|| {
+            invalid_amount(format!(
+                "Balance {} does not cover the fee reserve of {} lamports",
+                req.balance, req.fee_reserve
+            ))
+        })?;
+
+    let instruction = solana_sdk::system_instruction::transfer(&from, &to, lamports);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: instruction_output(instruction, &options)?,
+    }))
+}
+
+//
+// /token/amount/format
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormatAmountRequest {
+    #[serde(deserialize_with = "u64_from_str_or_number")]
+    pub raw: u64,
+    pub decimals: u8,
+    pub symbol: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct FormatAmountResponse {
+    pub display: String,
+}
+
+// Works on the decimal digits of `raw` so no amount loses precision the
+// way an f64 round-trip would.
+fn format_token_amount(raw: u64, decimals: u8) -> String {
+    let digits = format!("{:0>width$}", raw, width = usize::from(decimals) + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - usize::from(decimals));
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        grouped
+    } else {
+        format!("{}.{}", grouped, fraction)
+    }
+}
+
+pub async fn format_amount(
+    Json(req): Json<FormatAmountRequest>,
+) -> Result<Json<SuccessResponse<FormatAmountResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let mut display = format_token_amount(req.raw, req.decimals);
+    if let Some(symbol) = req.symbol.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        display.push(' ');
+        display.push_str(symbol);
+    }
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: FormatAmountResponse { display },
+    }))
+}
+
+//
+// /token/account/init
+//
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InitializeAccountRequest {
+    pub account: String,
+    pub mint: String,
+    pub owner: String,
+}
+
+#[derive(Serialize)]
+pub struct InitializeAccountResponse {
+    #[serde(flatten)]
+    pub instruction: InstructionResponse,
+    /// Size of the token account, for the `create_account` that must
+    /// precede this instruction.
+    pub account_space: usize,
+    pub rent_exempt_lamports: u64,
+}
+
+pub async fn initialize_account(
+    State(state): State<AppState>,
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<InitializeAccountRequest>,
+) -> Result<
+    Json<SuccessResponse<InstructionOutput<InitializeAccountResponse>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let account = parse_pubkey(&req.account, "account")?;
+    let mint = parse_pubkey(&req.mint, "mint")?;
+    let owner = parse_pubkey(&req.owner, "owner")?;
+
+    let instruction = spl_token_2022::instruction::initialize_account(
+        &state.token_program,
+        &account,
+        &mint,
+        &owner,
+    )
+    .map_err(instruction_error)?;
+
+    let data = instruction_output_with(instruction, &options, |instruction| {
+        InitializeAccountResponse {
+            instruction,
+            account_space: spl_token::state::Account::LEN,
+            rent_exempt_lamports: Rent::default()
+                .minimum_balance(spl_token::state::Account::LEN),
+        }
+    })?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data,
+    }))
+}
+
+//
+// /keypair/selftest
+//
+
+const SELFTEST_MESSAGE: &[u8] = b"solana-axum-server selftest";
+
+#[derive(Serialize)]
+pub struct SelftestResponse {
+    pub ok: bool,
+}
+
+// Runs the same generate, sign and verify path the real endpoints use. Any
+// failure means the deployment itself is broken, hence 500.
+pub async fn keypair_selftest()
+-> Result<Json<SuccessResponse<SelftestResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let failed = |step: &str, detail: String| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                success: false,
+                error: format!("Selftest failed at {}: {}", step, detail),
+                code: ErrorCode::Internal,
+            }),
+        )
+    };
+
+    let keypair = Keypair::new();
+    let signature = keypair.sign_message(SELFTEST_MESSAGE);
+
+    let public_key = ed25519_dalek::PublicKey::from_bytes(keypair.pubkey().as_ref())
+        .map_err(|e| failed("public key decoding", e.to_string()))?;
+    let signature = ed25519_dalek::Signature::from_bytes(signature.as_ref())
+        .map_err(|e| failed("signature decoding", e.to_string()))?;
+    public_key
+        .verify_strict(SELFTEST_MESSAGE, &signature)
+        .map_err(|e| failed("verification", e.to_string()))?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: SelftestResponse { ok: true },
+    }))
+}
+
+//
+// /instructions/custom
+//
+
+// Builds an instruction for any program by round-tripping the request
+// through `Instruction`, so every pubkey and the data are validated.
+pub async fn custom_instruction(
+    Query(options): Query<InstructionOptions>,
+    Json(req): Json<InstructionRequest>,
+) -> Result<Json<SuccessResponse<InstructionOutput>>, (StatusCode, Json<ErrorResponse>)> {
+    let instruction = parse_instruction_fields("", &req)?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: instruction_output(instruction, &options)?,
+    }))
+}
+
+//
+// /stats
+//
+
+pub async fn stats(State(state): State<AppState>) -> Json<SuccessResponse<StatsCounts>> {
+    Json(SuccessResponse {
+        success: true,
+        data: state.stats.counts(),
+    })
+}
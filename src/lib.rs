@@ -0,0 +1,287 @@
+use axum::{
+    Extension, Router,
+    routing::{get, post},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    sync::{Arc, atomic::AtomicU64},
+    time::Duration,
+};
+use tower_http::{catch_panic::CatchPanicLayer, compression::CompressionLayer};
+
+use crate::state::{
+    AppState, DEFAULT_IDEMPOTENCY_TTL, DEFAULT_MAX_MESSAGE_BYTES, DEFAULT_REQUEST_TIMEOUT,
+    DEFAULT_TOKEN_DECIMALS, IdempotencyCache, RateLimiter,
+};
+
+pub mod extract;
+pub mod handlers;
+pub mod middleware;
+pub mod state;
+
+pub fn app() -> Router {
+    app_with_rpc(rpc_client_from_env())
+}
+
+/// Builds the router with an explicit RPC client, or `None` to disable the
+/// endpoints that need one.
+pub fn app_with_rpc(rpc: Option<Arc<RpcClient>>) -> Router {
+    let state = AppState {
+        rate_limiter: rate_limiter_from_env(),
+        max_mint_amount: max_mint_amount_from_env(),
+        default_token_decimals: default_token_decimals_from_env(),
+        keypair_cache: Arc::new(IdempotencyCache::new(idempotency_ttl_from_env())),
+        request_timeout: request_timeout_from_env(),
+        keypair_counter: keypair_counter_from_env(),
+        token_program: token_program_from_env(),
+        max_message_bytes: max_message_bytes_from_env(),
+        ..AppState::default()
+    };
+
+    router(state, rpc, benchmarks_enabled())
+}
+
+/// Builds the router from explicit settings rather than the environment.
+/// `benchmarks` mounts the CPU-heavy `/benchmark/*` routes.
+pub fn router(state: AppState, rpc: Option<Arc<RpcClient>>, benchmarks: bool) -> Router {
+    let mut routes = Router::new()
+        .route("/keypair", post(handlers::generate_keypair))
+        .route("/token/create", post(handlers::create_token))
+        .route("/token/mint", post(handlers::mint_token))
+        .route("/message/sign", post(handlers::sign_message))
+        .route("/message/verify", post(handlers::verify_message))
+        .route("/send/sol", post(handlers::send_sol))
+        .route("/send/token", post(handlers::send_token))
+        .route("/transaction/build", post(handlers::build_transaction))
+        .route("/account/close-to-owner", post(handlers::close_account_to_owner))
+        .route("/message/canonicalize", post(handlers::canonicalize_message))
+        .route("/token/create/with-account", post(handlers::create_token_with_account))
+        .route("/token/approve/batch", post(handlers::approve_batch))
+        .route("/transaction/fit-check", post(handlers::fit_check))
+        .route("/account/rent", post(handlers::rent_minimum))
+        .route("/keypair/indexed", post(handlers::generate_indexed_keypair))
+        .route("/token/revoke/batch", post(handlers::revoke_batch))
+        .route("/send/token/ata", post(handlers::send_token_ata))
+        .route("/message/verify/pda-linked", post(handlers::verify_pda_linked))
+        .route("/transaction/build-sponsored", post(handlers::build_sponsored_transaction))
+        .route("/pubkey/validate", post(handlers::validate_pubkey))
+        .route("/token/validate-flow", post(handlers::validate_token_flow))
+        .route("/account/wsol-ata", post(handlers::wsol_ata))
+        .route("/message/sign-batch", post(handlers::sign_batch))
+        .route("/message/verify/threshold", post(handlers::verify_threshold))
+        .route("/token/transfer", post(handlers::transfer_token))
+        .route("/token/decode-checked", post(handlers::decode_checked))
+        .route("/token/prepare-create", post(handlers::prepare_create_token))
+        .route("/keypair/derive-pda", post(handlers::derive_pda))
+        .route("/transaction/blockhash-status", post(handlers::blockhash_status))
+        .route("/token/set-authority/batch", post(handlers::set_authority_batch))
+        .route("/token/set-authority", post(handlers::set_authority))
+        .route("/message/sign-timestamped", post(handlers::sign_timestamped))
+        .route("/message/verify-timestamped", post(handlers::verify_timestamped))
+        .route("/signature/validate", post(handlers::validate_signature))
+        .route("/token/amount/convert", post(handlers::ui_to_raw))
+        .route("/transaction/from-responses", post(handlers::transaction_from_responses))
+        .route("/metrics", get(handlers::metrics))
+        .route("/message/verify-batch", post(handlers::verify_batch))
+        .route("/keypair/pubkey", post(handlers::recover_pubkey))
+        .route("/token/create/extensions", post(handlers::create_token_with_extensions))
+        .route("/send/sol/memo", post(handlers::send_sol_with_memo))
+        .route("/transaction/decode", post(handlers::decode_transaction))
+        .route("/keypair/mnemonic", post(handlers::generate_mnemonic))
+        .route("/keypair/from-mnemonic", post(handlers::keypair_from_mnemonic))
+        .route("/fees/estimate", post(handlers::fees_estimate))
+        .route("/instructions/compute-budget", post(handlers::compute_budget))
+        .route("/token/create/new-mint", post(handlers::create_token_new_mint))
+        .route("/version", get(handlers::version))
+        .route("/send/token/batch", post(handlers::send_token_batch))
+        .route("/token/multisig/create", post(handlers::create_multisig))
+        .route("/blockhash/validate", post(handlers::validate_blockhash))
+        .route("/keypair/vanity", post(handlers::vanity_keypair))
+        .route("/send/sol/drain", post(handlers::drain_sol))
+        .route("/token/amount/format", post(handlers::format_amount))
+        .route("/token/account/init", post(handlers::initialize_account))
+        .route("/keypair/selftest", post(handlers::keypair_selftest))
+        .route("/instructions/custom", post(handlers::custom_instruction))
+        .route("/stats", get(handlers::stats));
+
+    // Benchmarks burn CPU on the request path, so they stay off unless the
+    // operator opts in with BENCHMARKS=true.
+    if benchmarks {
+        routes = routes.route("/benchmark/keypair", get(handlers::benchmark_keypair));
+    }
+
+    with_layers(routes, state, rpc)
+}
+
+/// Wraps `routes` in the middleware stack every deployment runs behind.
+pub fn with_layers(
+    routes: Router<AppState>,
+    state: AppState,
+    rpc: Option<Arc<RpcClient>>,
+) -> Router {
+    routes
+        .layer(CatchPanicLayer::custom(middleware::handle_panic))
+        .layer(Extension(rpc))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::timeout,
+        ))
+        .layer(axum::middleware::from_fn(middleware::envelope))
+        .layer(axum::middleware::from_fn(middleware::pretty))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::rate_limit,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::track_metrics,
+        ))
+        .layer(axum::middleware::from_fn(middleware::response_time))
+        .layer(axum::middleware::from_fn(middleware::request_id))
+        // Outermost, so the envelope and pretty middlewares still see plain
+        // JSON bodies.
+        .layer(CompressionLayer::new())
+        .with_state(state)
+}
+
+fn rpc_client_from_env() -> Option<Arc<RpcClient>> {
+    std::env::var("RPC_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| Arc::new(RpcClient::new(url)))
+}
+
+fn rate_limiter_from_env() -> Option<Arc<RateLimiter>> {
+    let value = std::env::var("RATE_LIMIT_PER_MINUTE").ok()?;
+    match value.parse::<u32>() {
+        Ok(0) => None,
+        Ok(per_minute) => Some(Arc::new(RateLimiter::new(per_minute))),
+        Err(_) => {
+            eprintln!("Invalid RATE_LIMIT_PER_MINUTE value: {}, rate limiting disabled", value);
+            None
+        }
+    }
+}
+
+fn max_mint_amount_from_env() -> Option<u64> {
+    let value = std::env::var("MAX_MINT_AMOUNT").ok()?;
+    match value.parse::<u64>() {
+        Ok(max) => Some(max),
+        Err(_) => {
+            eprintln!("Invalid MAX_MINT_AMOUNT value: {}, no mint ceiling applied", value);
+            None
+        }
+    }
+}
+
+fn default_token_decimals_from_env() -> u8 {
+    let Ok(value) = std::env::var("DEFAULT_TOKEN_DECIMALS") else {
+        return DEFAULT_TOKEN_DECIMALS;
+    };
+    match value.parse::<u8>() {
+        Ok(decimals) => decimals,
+        Err(_) => {
+            eprintln!(
+                "Invalid DEFAULT_TOKEN_DECIMALS value: {}, using {}",
+                value, DEFAULT_TOKEN_DECIMALS
+            );
+            DEFAULT_TOKEN_DECIMALS
+        }
+    }
+}
+
+fn idempotency_ttl_from_env() -> Duration {
+    let Ok(value) = std::env::var("IDEMPOTENCY_TTL_SECS") else {
+        return DEFAULT_IDEMPOTENCY_TTL;
+    };
+    match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            eprintln!(
+                "Invalid IDEMPOTENCY_TTL_SECS value: {}, using {}",
+                value,
+                DEFAULT_IDEMPOTENCY_TTL.as_secs()
+            );
+            DEFAULT_IDEMPOTENCY_TTL
+        }
+    }
+}
+
+fn request_timeout_from_env() -> Duration {
+    let Ok(value) = std::env::var("REQUEST_TIMEOUT_SECS") else {
+        return DEFAULT_REQUEST_TIMEOUT;
+    };
+    match value.parse::<u64>() {
+        Ok(secs) if secs > 0 => Duration::from_secs(secs),
+        _ => {
+            eprintln!(
+                "Invalid REQUEST_TIMEOUT_SECS value: {}, using {}",
+                value,
+                DEFAULT_REQUEST_TIMEOUT.as_secs()
+            );
+            DEFAULT_REQUEST_TIMEOUT
+        }
+    }
+}
+
+fn keypair_counter_from_env() -> Option<Arc<AtomicU64>> {
+    if !matches!(
+        std::env::var("DETERMINISTIC_KEYPAIRS").as_deref(),
+        Ok("true") | Ok("1")
+    ) {
+        return None;
+    }
+
+    eprintln!(
+        "WARNING: DETERMINISTIC_KEYPAIRS is enabled. /keypair returns predictable keys \
+         that anyone can reproduce; never use this outside of tests."
+    );
+    Some(Arc::new(AtomicU64::new(0)))
+}
+
+// Accepts the same names as the per-request `program` field, or either
+// program's address.
+fn token_program_from_env() -> Pubkey {
+    let Ok(value) = std::env::var("TOKEN_PROGRAM") else {
+        return spl_token::ID;
+    };
+    match value.as_str() {
+        "token" => spl_token::ID,
+        "token-2022" => spl_token_2022::ID,
+        other => match other.parse::<Pubkey>() {
+            Ok(id) if id == spl_token::ID || id == spl_token_2022::ID => id,
+            _ => {
+                eprintln!(
+                    "Invalid TOKEN_PROGRAM value: {}, expected \"token\" or \"token-2022\"; \
+                     using the SPL Token program",
+                    value
+                );
+                spl_token::ID
+            }
+        },
+    }
+}
+
+fn max_message_bytes_from_env() -> usize {
+    let Ok(value) = std::env::var("MAX_MESSAGE_BYTES") else {
+        return DEFAULT_MAX_MESSAGE_BYTES;
+    };
+    match value.parse::<usize>() {
+        Ok(max) if max > 0 => max,
+        _ => {
+            eprintln!(
+                "Invalid MAX_MESSAGE_BYTES value: {}, using {}",
+                value, DEFAULT_MAX_MESSAGE_BYTES
+            );
+            DEFAULT_MAX_MESSAGE_BYTES
+        }
+    }
+}
+
+fn benchmarks_enabled() -> bool {
+    matches!(
+        std::env::var("BENCHMARKS").as_deref(),
+        Ok("true") | Ok("1")
+    )
+}
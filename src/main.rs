@@ -1,28 +1,62 @@
-use axum::{Router, routing::post};
-use std::net::SocketAddr;
-
-mod handlers;
+use solana_axum_server::app;
+use std::net::{IpAddr, SocketAddr};
 
 #[tokio::main]
 async fn main() {
-    let app = Router::new()
-        .route("/keypair", post(handlers::generate_keypair))
-        .route("/token/create", post(handlers::create_token))
-        .route("/token/mint", post(handlers::mint_token))
-        .route("/message/sign", post(handlers::sign_message))
-        .route("/message/verify", post(handlers::verify_message))
-        .route("/send/sol", post(handlers::send_sol))
-        .route("/send/token", post(handlers::send_token));
+    let app = app();
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".into());
-    let addr = SocketAddr::from(([0, 0, 0, 0], port.parse().unwrap()));
+    let port_number: u16 = match port.parse() {
+        Ok(p) => p,
+        Err(_) => {
+            eprintln!("Invalid PORT value: {}", port);
+            std::process::exit(1);
+        }
+    };
+    let host = std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".into());
+    let ip: IpAddr = match host.parse() {
+        Ok(ip) => ip,
+        Err(_) => {
+            eprintln!("Invalid HOST value: {}", host);
+            std::process::exit(1);
+        }
+    };
+    let addr = SocketAddr::new(ip, port_number);
     println!(
-        "Server running on 0.0.0.0:{} (env PORT = {})",
-        port,
+        "Server running on {} (env HOST = {}, PORT = {})",
+        addr,
+        std::env::var("HOST").unwrap_or_else(|_| "not set".into()),
         std::env::var("PORT").unwrap_or_else(|_| "not set".into())
     );
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
         .await
         .unwrap();
 }
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    println!("shutting down gracefully");
+}
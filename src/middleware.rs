@@ -0,0 +1,250 @@
+use axum::{
+    Json,
+    body::{Full, boxed},
+    extract::{ConnectInfo, MatchedPath, State},
+    http::{HeaderMap, HeaderValue, Request, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{any::Any, net::SocketAddr, time::Instant};
+
+use crate::{
+    handlers::{ErrorCode, ErrorResponse},
+    state::AppState,
+};
+
+pub const RESPONSE_TIME_HEADER: &str = "x-response-time-ms";
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Client-supplied ids longer than this are replaced rather than echoed.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation id for the current request, available to handlers through
+/// `Extension<RequestId>`.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+pub async fn response_time<B>(request: Request<B>, next: Next<B>) -> Response {
+    let start = Instant::now();
+    let mut response = next.run(request).await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    response
+        .headers_mut()
+        .insert(RESPONSE_TIME_HEADER, HeaderValue::from(elapsed_ms));
+    response
+}
+
+pub async fn track_metrics<B>(
+    State(state): State<AppState>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let method = request.method().to_string();
+    // Unmatched paths share one label so scanners can't grow the metric set.
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+    state
+        .metrics
+        .record(&method, &path, status.is_client_error() || status.is_server_error());
+    response
+}
+
+pub async fn rate_limit<B>(
+    State(state): State<AppState>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Some(limiter) = &state.rate_limiter else {
+        return next.run(request).await;
+    };
+
+    let client = client_ip(request.headers(), request.extensions().get());
+    match limiter.check(&client) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after_secs) => {
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse {
+                    success: false,
+                    error: format!(
+                        "Rate limit exceeded, retry in {} seconds",
+                        retry_after_secs
+                    ),
+                    code: ErrorCode::RateLimited,
+                }),
+            )
+                .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+    }
+}
+
+/// Answers with 408 when the rest of the stack takes longer than
+/// `AppState::request_timeout`. The handler future is dropped at that point.
+pub async fn timeout<B>(
+    State(state): State<AppState>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    match tokio::time::timeout(state.request_timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => (
+            StatusCode::REQUEST_TIMEOUT,
+            Json(ErrorResponse {
+                success: false,
+                error: format!(
+                    "Request timed out after {} seconds",
+                    state.request_timeout.as_secs_f64()
+                ),
+                code: ErrorCode::RequestTimeout,
+            }),
+        )
+            .into_response(),
+    }
+}
+
+// Railway terminates connections at its proxy, so the peer address is the
+// proxy's. Clients can prepend anything they like to X-Forwarded-For, so only
+// the last entry, appended by the proxy itself, identifies the caller.
+pub(crate) fn client_ip(headers: &HeaderMap, peer: Option<&ConnectInfo<SocketAddr>>) -> String {
+    let forwarded = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty());
+    if let Some(ip) = forwarded {
+        return ip.to_string();
+    }
+
+    peer.map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Strips the `{ success, data }` envelope from successful JSON responses
+/// when the request carries `?envelope=false`; the status code alone then
+/// signals success. Error responses keep their envelope.
+pub async fn envelope<B>(request: Request<B>, next: Next<B>) -> Response {
+    let bare = has_query_pair(&request, "envelope=false");
+
+    let response = next.run(request).await;
+    if !bare || !response.status().is_success() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    let data = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut envelope)) if envelope.contains_key("success") => {
+            envelope.remove("data")
+        }
+        _ => None,
+    };
+
+    let body = match data {
+        Some(data) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Full::from(data.to_string())
+        }
+        None => Full::from(bytes),
+    };
+    Response::from_parts(parts, boxed(body))
+}
+
+/// Re-serializes JSON responses with indentation when the request carries
+/// `?pretty=true`, for reading output from curl.
+pub async fn pretty<B>(request: Request<B>, next: Next<B>) -> Response {
+    let pretty = has_query_pair(&request, "pretty=true");
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+    {
+        Some(pretty) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Full::from(pretty)
+        }
+        None => Full::from(bytes),
+    };
+    Response::from_parts(parts, boxed(body))
+}
+
+fn has_query_pair<B>(request: &Request<B>, pair: &str) -> bool {
+    request
+        .uri()
+        .query()
+        .is_some_and(|query| query.split('&').any(|candidate| candidate == pair))
+}
+
+pub async fn request_id<B>(mut request: Request<B>, next: Next<B>) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = next.run(request).await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Turns a handler panic into a 500 with the usual error envelope instead
+/// of a dropped connection. Used with `CatchPanicLayer::custom`.
+pub fn handle_panic(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let detail = if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    eprintln!("handler panicked: {}", detail);
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            success: false,
+            error: "Internal server error".into(),
+            code: ErrorCode::Internal,
+        }),
+    )
+        .into_response()
+}
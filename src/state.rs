@@ -0,0 +1,273 @@
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(300);
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Shared state handed to every handler and middleware through `State`.
+#[derive(Clone)]
+pub struct AppState {
+    pub metrics: Arc<Metrics>,
+    /// `None` when RATE_LIMIT_PER_MINUTE is unset, which disables throttling.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Sanity ceiling for `/token/mint` amounts, from MAX_MINT_AMOUNT.
+    pub max_mint_amount: Option<u64>,
+    /// Decimals `/send/token` assumes for the mint, from
+    /// DEFAULT_TOKEN_DECIMALS.
+    pub default_token_decimals: u8,
+    /// Keypairs handed out under an `Idempotency-Key`, so a retried
+    /// `/keypair` call gets the same key back.
+    pub keypair_cache: Arc<IdempotencyCache>,
+    /// Requests still running after this long are answered with 408.
+    pub request_timeout: Duration,
+    /// Set by DETERMINISTIC_KEYPAIRS for CI: `/keypair` then derives the
+    /// n-th key from this counter instead of the OS RNG. Never use it for
+    /// real funds.
+    pub keypair_counter: Option<Arc<AtomicU64>>,
+    /// Token program the token endpoints build for unless a request names
+    /// one, from TOKEN_PROGRAM.
+    pub token_program: Pubkey,
+    /// Largest `message` `/message/sign` and `/message/verify` accept, from
+    /// MAX_MESSAGE_BYTES.
+    pub max_message_bytes: usize,
+    pub stats: Arc<Stats>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            metrics: Arc::default(),
+            rate_limiter: None,
+            max_mint_amount: None,
+            default_token_decimals: DEFAULT_TOKEN_DECIMALS,
+            keypair_cache: Arc::new(IdempotencyCache::new(DEFAULT_IDEMPOTENCY_TTL)),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            keypair_counter: None,
+            token_program: spl_token::ID,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            stats: Arc::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EndpointMetrics {
+    pub requests: AtomicU64,
+    pub errors: AtomicU64,
+}
+
+/// Per-endpoint request and error counters, keyed by method and matched
+/// route so path parameters can't blow up the label set.
+#[derive(Default)]
+pub struct Metrics {
+    endpoints: RwLock<BTreeMap<(String, String), Arc<EndpointMetrics>>>,
+}
+
+impl Metrics {
+    pub fn record(&self, method: &str, path: &str, is_error: bool) {
+        let endpoint = self.endpoint(method, path);
+        endpoint.requests.fetch_add(1, Ordering::Relaxed);
+        if is_error {
+            endpoint.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn endpoint(&self, method: &str, path: &str) -> Arc<EndpointMetrics> {
+        let key = (method.to_string(), path.to_string());
+        if let Some(endpoint) = self.endpoints.read().unwrap().get(&key) {
+            return endpoint.clone();
+        }
+        self.endpoints.write().unwrap().entry(key).or_default().clone()
+    }
+
+    /// Renders the counters in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let endpoints = self.endpoints.read().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Total HTTP requests handled.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for ((method, path), endpoint) in endpoints.iter() {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",path=\"{}\"}} {}",
+                method,
+                path,
+                endpoint.requests.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str("# HELP http_request_errors_total Requests answered with a 4xx or 5xx status.\n");
+        out.push_str("# TYPE http_request_errors_total counter\n");
+        for ((method, path), endpoint) in endpoints.iter() {
+            let _ = writeln!(
+                out,
+                "http_request_errors_total{{method=\"{}\",path=\"{}\"}} {}",
+                method,
+                path,
+                endpoint.errors.load(Ordering::Relaxed)
+            );
+        }
+
+        out
+    }
+}
+
+// Past this many tracked clients, idle buckets are dropped so a flood of
+// distinct addresses can't grow the map without bound.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token bucket holding up to `per_minute` tokens and refilling
+/// continuously at `per_minute` tokens a minute.
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        RateLimiter {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token for `client`, or returns how many seconds to wait
+    /// before the next one is available.
+    pub fn check(&self, client: &str) -> Result<(), u64> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), u64> {
+        let capacity = f64::from(self.per_minute);
+        let refill_per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens + elapsed * refill_per_sec < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) * 60.0 / capacity).ceil() as u64)
+        }
+    }
+}
+
+// Past this many cached keys, the oldest entry is evicted to make room, so a
+// client cycling through fresh keys can't grow the map without bound.
+const MAX_CACHED_KEYS: usize = 10_000;
+
+/// Remembers a value per client and idempotency key for `ttl`. Entries are
+/// scoped to the client so one caller can't replay another's key, and
+/// expired entries are swept on every access.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), (Instant, Vec<u8>)>>,
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        IdempotencyCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the value `client` stored under `key`, or stores and returns
+    /// the result of `create` if there is none or it has expired.
+    pub fn get_or_insert_with(
+        &self,
+        client: &str,
+        key: &str,
+        create: impl FnOnce() -> Vec<u8>,
+    ) -> Vec<u8> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (created, _)| now.duration_since(*created) < self.ttl);
+
+        let entry_key = (client.to_string(), key.to_string());
+        if entries.len() >= MAX_CACHED_KEYS && !entries.contains_key(&entry_key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (created, _))| *created)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries
+            .entry(entry_key)
+            .or_insert_with(|| (now, create()))
+            .1
+            .clone()
+    }
+}
+
+/// How much the server has produced since it started, for `/stats`.
+/// Nothing is persisted, so the counts reset on restart.
+#[derive(Default)]
+pub struct Stats {
+    keypairs: AtomicU64,
+    tokens: AtomicU64,
+    signatures: AtomicU64,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct StatsCounts {
+    pub keypairs: u64,
+    pub tokens: u64,
+    pub signatures: u64,
+}
+
+impl Stats {
+    pub fn record_keypairs(&self, count: u64) {
+        self.keypairs.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_tokens(&self, count: u64) {
+        self.tokens.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_signatures(&self, count: u64) {
+        self.signatures.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self) -> StatsCounts {
+        StatsCounts {
+            keypairs: self.keypairs.load(Ordering::Relaxed),
+            tokens: self.tokens.load(Ordering::Relaxed),
+            signatures: self.signatures.load(Ordering::Relaxed),
+        }
+    }
+}
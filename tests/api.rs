@@ -0,0 +1,2998 @@
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use solana_axum_server::{app, middleware, state::AppState};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use tower::ServiceExt;
+
+async fn send(method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn post_json(uri: &str, body: Value) -> (StatusCode, Value) {
+    send(Method::POST, uri, Some(body)).await
+}
+
+fn new_pubkey() -> String {
+    Pubkey::new_unique().to_string()
+}
+
+fn instruction_json(instruction: &solana_sdk::instruction::Instruction) -> Value {
+    json!({
+        "program_id": instruction.program_id.to_string(),
+        "accounts": instruction.accounts.iter().map(|meta| json!({
+            "pubkey": meta.pubkey.to_string(),
+            "is_signer": meta.is_signer,
+            "is_writable": meta.is_writable
+        })).collect::<Vec<_>>(),
+        "data": base64::encode(&instruction.data)
+    })
+}
+
+fn assert_error(status: StatusCode, body: &Value, code: &str) {
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], code);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn keypair_returns_pubkey_and_secret() {
+    let (status, body) = send(Method::POST, "/keypair", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["success"], true);
+    let secret = bs58::decode(body["data"]["secret"].as_str().unwrap())
+        .into_vec()
+        .unwrap();
+    let keypair = Keypair::from_bytes(&secret).unwrap();
+    assert_eq!(body["data"]["pubkey"], keypair.pubkey().to_string());
+}
+
+#[tokio::test]
+async fn keypair_simulated_failure() {
+    let (status, body) = send(Method::POST, "/keypair?fail=true", None).await;
+
+    assert_error(status, &body, "SIMULATED_FAILURE");
+}
+
+#[tokio::test]
+async fn keypair_can_return_secret_bytes() {
+    let (status, body) = send(Method::POST, "/keypair?format=bytes", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes: Vec<u8> = serde_json::from_value(body["data"]["secret_bytes"].clone()).unwrap();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(
+        bs58::encode(&bytes).into_string(),
+        body["data"]["secret"].as_str().unwrap()
+    );
+
+    let (_, body) = send(Method::POST, "/keypair", None).await;
+    assert!(body["data"].get("secret_bytes").is_none());
+}
+
+#[tokio::test]
+async fn keypair_reuses_result_for_same_idempotency_key() {
+    let app = app();
+    let mut pubkeys = Vec::new();
+    for key in ["retry-0000000001", "retry-0000000001", "retry-0000000002"] {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/keypair")
+            .header("Idempotency-Key", key)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        pubkeys.push(body["data"]["pubkey"].as_str().unwrap().to_string());
+    }
+
+    assert_eq!(pubkeys[0], pubkeys[1]);
+    assert_ne!(pubkeys[0], pubkeys[2]);
+}
+
+#[tokio::test]
+async fn keypair_idempotency_keys_are_scoped_per_client() {
+    let app = app();
+    let mut pubkeys = Vec::new();
+    for client in ["10.0.0.1", "10.0.0.2"] {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/keypair")
+            .header("Idempotency-Key", "shared-key-000001")
+            .header("x-forwarded-for", client)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        pubkeys.push(body["data"]["pubkey"].as_str().unwrap().to_string());
+    }
+
+    assert_ne!(pubkeys[0], pubkeys[1]);
+}
+
+#[tokio::test]
+async fn keypair_rejects_short_idempotency_key() {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/keypair")
+        .header("Idempotency-Key", "retry-1")
+        .body(Body::empty())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn create_token_builds_initialize_mint() {
+    let mint = new_pubkey();
+    let (status, body) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": new_pubkey(), "mint": mint, "decimals": 6 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["program_id"], spl_token::ID.to_string());
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], mint);
+    assert_eq!(body["data"]["accounts"][0]["is_writable"], true);
+    assert!(body["data"]["instruction_data"].is_string());
+    assert_eq!(body["data"]["mint_space"], 82);
+    assert_eq!(
+        body["data"]["rent_exempt_lamports"],
+        solana_sdk::rent::Rent::default().minimum_balance(82)
+    );
+}
+
+#[tokio::test]
+async fn create_token_can_use_initialize_mint2() {
+    let mint = new_pubkey();
+    let (status, body) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": new_pubkey(), "mint": mint, "decimals": 6, "use_mint2": true }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    // initialize_mint2 takes only the mint, with no rent sysvar.
+    assert_eq!(
+        body["data"]["accounts"],
+        json!([{ "pubkey": mint, "is_signer": false, "is_writable": true }])
+    );
+    let data = base64::decode(body["data"]["instruction_data"].as_str().unwrap()).unwrap();
+    assert_eq!(data[0], 20);
+}
+
+#[tokio::test]
+async fn create_token_flags_mint_equal_to_authority() {
+    let key = new_pubkey();
+    let request = |strict: bool| {
+        json!({ "mintAuthority": key, "mint": key, "decimals": 6, "strict": strict })
+    };
+
+    let (status, body) = post_json("/token/create", request(false)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["warnings"].as_array().unwrap().len(), 1);
+
+    let (status, body) = post_json("/token/create", request(true)).await;
+    assert_error(status, &body, "INVALID_INPUT");
+
+    let (_, body) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": new_pubkey(), "mint": new_pubkey(), "decimals": 6 }),
+    )
+    .await;
+    assert!(body["data"].get("warnings").is_none());
+}
+
+#[tokio::test]
+async fn create_token_rejects_bad_pubkey() {
+    let (status, body) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": new_pubkey(), "mint": "not-a-pubkey", "decimals": 6 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_PUBKEY");
+}
+
+#[tokio::test]
+async fn create_token_rejects_bad_decimals() {
+    let (status, body) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": new_pubkey(), "mint": new_pubkey(), "decimals": 300 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn mint_token_builds_mint_to() {
+    let authority = new_pubkey();
+    let (status, body) = post_json(
+        "/token/mint",
+        json!({
+            "mint": new_pubkey(),
+            "destination": new_pubkey(),
+            "authority": authority,
+            "amount": 1_000_000
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let accounts = body["data"]["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), 3);
+    assert_eq!(accounts[2]["pubkey"], authority);
+    assert_eq!(accounts[2]["is_signer"], true);
+
+    let roles: Vec<&str> = body["data"]["labeled_accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|account| account["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["mint", "destination", "authority"]);
+    assert_eq!(body["data"]["labeled_accounts"][2]["pubkey"], authority);
+}
+
+#[tokio::test]
+async fn mint_token_rejects_bad_authority() {
+    let (status, body) = post_json(
+        "/token/mint",
+        json!({
+            "mint": new_pubkey(),
+            "destination": new_pubkey(),
+            "authority": "bad",
+            "amount": 1
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_PUBKEY");
+}
+
+#[tokio::test]
+async fn sign_then_verify_round_trip() {
+    let keypair = Keypair::new();
+    let secret = bs58::encode(keypair.to_bytes()).into_string();
+
+    let (status, signed) = post_json(
+        "/message/sign",
+        json!({ "message": "hello", "secret": secret }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(signed["data"]["public_key"], keypair.pubkey().to_string());
+
+    let (status, verified) = post_json(
+        "/message/verify",
+        json!({
+            "message": "hello",
+            "signature": signed["data"]["signature"],
+            "pubkey": keypair.pubkey().to_string()
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(verified["data"]["valid"], true);
+
+    let (_, tampered) = post_json(
+        "/message/verify",
+        json!({
+            "message": "hello!",
+            "signature": signed["data"]["signature"],
+            "pubkey": keypair.pubkey().to_string()
+        }),
+    )
+    .await;
+    assert_eq!(tampered["data"]["valid"], false);
+    assert_eq!(
+        tampered["data"]["reason"],
+        "signature does not match the message and public key"
+    );
+    assert!(verified["data"].get("reason").is_none());
+}
+
+#[tokio::test]
+async fn sign_message_rejects_bad_secret() {
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "hello", "secret": "0OIl" }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_SECRET_KEY");
+}
+
+#[tokio::test]
+async fn sign_message_accepts_binary_encodings() {
+    let keypair = Keypair::new();
+    let secret = bs58::encode(keypair.to_bytes()).into_string();
+    let payload = [0xde, 0xad, 0xbe, 0xef, 0x00];
+
+    for (message, encoding) in [
+        (hex::encode(payload), "hex"),
+        (base64::encode(payload), "base64"),
+    ] {
+        let (status, body) = post_json(
+            "/message/sign",
+            json!({ "message": message, "secret": secret, "message_encoding": encoding }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["data"]["signature"],
+            base64::encode(keypair.sign_message(&payload))
+        );
+    }
+
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "zz", "secret": secret, "message_encoding": "hex" }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_ENCODING");
+
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "hi", "secret": secret, "message_encoding": "utf16" }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn sign_message_can_omit_public_key() {
+    let keypair = Keypair::new();
+    let secret = bs58::encode(keypair.to_bytes()).into_string();
+
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "hello", "secret": secret, "include_pubkey": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].get("public_key").is_none());
+    assert!(body["data"]["signature"].is_string());
+
+    let (_, body) = post_json("/message/sign", json!({ "message": "hello", "secret": secret })).await;
+    assert_eq!(body["data"]["public_key"], keypair.pubkey().to_string());
+}
+
+#[tokio::test]
+async fn sign_message_rejects_degenerate_secret() {
+    let zeros = bs58::encode([0u8; 64]).into_string();
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "hello", "secret": zeros }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_SECRET_KEY");
+
+    // Valid secret half paired with someone else's public key.
+    let mut bytes = Keypair::new().to_bytes();
+    bytes[32..].copy_from_slice(&Keypair::new().pubkey().to_bytes());
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": "hello", "secret": bs58::encode(bytes).into_string() }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_SECRET_KEY");
+    assert_eq!(body["error"], "Invalid secret key material");
+}
+
+#[tokio::test]
+async fn verify_message_rejects_short_signature() {
+    let (status, body) = post_json(
+        "/message/verify",
+        json!({
+            "message": "hello",
+            "signature": base64::encode([0u8; 32]),
+            "pubkey": new_pubkey()
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_SIGNATURE");
+    assert_eq!(body["error"], "Signature must be 64 bytes, got 32");
+}
+
+#[tokio::test]
+async fn verify_message_includes_digest() {
+    use sha2::{Digest, Sha256};
+
+    let keypair = Keypair::new();
+    let signature = keypair.sign_message(b"audit me");
+    let (status, body) = post_json(
+        "/message/verify",
+        json!({
+            "message": "audit me",
+            "signature": base64::encode(signature),
+            "pubkey": keypair.pubkey().to_string(),
+            "includeDigest": true
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected: String = Sha256::digest(b"audit me")
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(body["data"]["messageSha256"], expected);
+    assert_eq!(body["data"]["messageLength"], 8);
+}
+
+#[tokio::test]
+async fn send_sol_builds_transfer() {
+    let from = new_pubkey();
+    let to = new_pubkey();
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": from, "to": to, "lamports": 5000 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["data"]["program_id"],
+        solana_sdk::system_program::ID.to_string()
+    );
+    assert_eq!(
+        body["data"]["accounts"],
+        json!([
+            { "pubkey": from, "is_signer": true, "is_writable": true },
+            { "pubkey": to, "is_signer": false, "is_writable": true }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn send_sol_rejects_bad_address() {
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": "bad", "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_PUBKEY");
+    assert_eq!(body["error"], "Invalid from address: must decode to 32 bytes, got 2");
+}
+
+#[tokio::test]
+async fn send_sol_trims_whitespace_around_addresses() {
+    let from = new_pubkey();
+    let to = new_pubkey();
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": format!("  {}\n", from), "to": format!("\t{}", to), "lamports": 5000 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], from);
+    assert_eq!(body["data"]["accounts"][1]["pubkey"], to);
+}
+
+#[tokio::test]
+async fn send_sol_reports_blank_address_as_missing() {
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": "   ", "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    assert_error(status, &body, "MISSING_FIELD");
+    assert_eq!(body["error"], "Missing required field: from");
+}
+
+#[tokio::test]
+async fn send_token_builds_transfer_checked() {
+    let owner = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/token",
+        json!({
+            "destination": destination.to_string(),
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "amount": 10
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["program_id"], spl_token::ID.to_string());
+    assert_eq!(body["data"]["accounts"][3]["pubkey"], owner.to_string());
+    assert_eq!(body["data"]["accounts"][3]["is_signer"], true);
+    assert_eq!(body["data"]["accounts"][3]["is_writable"], false);
+
+    let source = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+    let destination_ata =
+        spl_associated_token_account::get_associated_token_address(&destination, &mint);
+    assert_eq!(body["data"]["source"], source.to_string());
+    assert_eq!(body["data"]["destination_ata"], destination_ata.to_string());
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], source.to_string());
+    assert_eq!(body["data"]["accounts"][2]["pubkey"], destination_ata.to_string());
+}
+
+#[tokio::test]
+async fn build_transaction_serializes_message() {
+    let fee_payer = Keypair::new().pubkey();
+    let recipient = Pubkey::new_unique();
+    let instruction = solana_sdk::system_instruction::transfer(&fee_payer, &recipient, 1);
+    let blockhash = solana_sdk::hash::Hash::new_unique();
+
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": fee_payer.to_string(),
+            "recent_blockhash": blockhash.to_string(),
+            "instructions": [instruction_json(&instruction)]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected = solana_sdk::message::Message::new_with_blockhash(
+        &[instruction],
+        Some(&fee_payer),
+        &blockhash,
+    );
+    assert_eq!(body["data"]["message"], base64::encode(expected.serialize()));
+}
+
+#[tokio::test]
+async fn build_transaction_compiles_v0_with_lookup_tables() {
+    let fee_payer = Keypair::new().pubkey();
+    let recipient = Pubkey::new_unique();
+    let table = Pubkey::new_unique();
+    let instruction = solana_sdk::system_instruction::transfer(&fee_payer, &recipient, 1);
+    let blockhash = solana_sdk::hash::Hash::new_unique();
+    let request = |version: &str| {
+        json!({
+            "fee_payer": fee_payer.to_string(),
+            "recent_blockhash": blockhash.to_string(),
+            "instructions": [instruction_json(&instruction)],
+            "version": version,
+            "address_lookup_tables": [
+                { "key": table.to_string(), "addresses": [recipient.to_string()] }
+            ]
+        })
+    };
+
+    let (status, body) = post_json("/transaction/build", request("0")).await;
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["message"].as_str().unwrap()).unwrap();
+    let message: solana_sdk::message::VersionedMessage = bincode::deserialize(&bytes).unwrap();
+    let solana_sdk::message::VersionedMessage::V0(message) = message else {
+        panic!("expected a v0 message");
+    };
+    assert_eq!(message.account_keys[0], fee_payer);
+    assert!(!message.account_keys.contains(&recipient));
+    assert_eq!(message.address_table_lookups.len(), 1);
+    assert_eq!(message.address_table_lookups[0].account_key, table);
+    assert_eq!(message.address_table_lookups[0].writable_indexes, vec![0]);
+
+    let (status, body) = post_json("/transaction/build", request("legacy")).await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn build_transaction_rejects_bad_blockhash() {
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": new_pubkey(),
+            "recent_blockhash": "not-a-hash",
+            "instructions": []
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_BLOCKHASH");
+}
+
+#[tokio::test]
+async fn close_to_owner_routes_rent_to_owner() {
+    let account = new_pubkey();
+    let owner = new_pubkey();
+    let (status, body) = post_json(
+        "/account/close-to-owner",
+        json!({ "account": account, "owner": owner }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let accounts = body["data"]["accounts"].as_array().unwrap();
+    assert_eq!(accounts[0]["pubkey"], account);
+    assert_eq!(accounts[1]["pubkey"], owner);
+    assert_eq!(accounts[1]["is_writable"], true);
+    assert_eq!(accounts[2]["pubkey"], owner);
+    assert_eq!(accounts[2]["is_signer"], true);
+}
+
+#[tokio::test]
+async fn canonicalize_ignores_member_order() {
+    let (status, a) = post_json(
+        "/message/canonicalize",
+        json!({ "b": [1, { "y": true, "x": null }], "a": "text", "n": 1.50 }),
+    )
+    .await;
+    let (_, b) = post_json(
+        "/message/canonicalize",
+        json!({ "n": 1.5, "a": "text", "b": [1, { "x": null, "y": true }] }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(a["data"]["canonical"], b["data"]["canonical"]);
+    assert_eq!(
+        a["data"]["canonical"],
+        r#"{"a":"text","b":[1,{"x":null,"y":true}],"n":1.5}"#
+    );
+}
+
+#[tokio::test]
+async fn canonicalize_rounds_integers_beyond_double_precision() {
+    let (status, body) = post_json(
+        "/message/canonicalize",
+        json!({ "big": 9_007_199_254_740_993u64, "neg": -9_007_199_254_740_995i64, "one": 1 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["data"]["canonical"],
+        r#"{"big":9007199254740992,"neg":-9007199254740996,"one":1}"#
+    );
+}
+
+#[tokio::test]
+async fn create_token_with_account_returns_both_instructions() {
+    let mint = new_pubkey();
+    let (status, body) = post_json(
+        "/token/create/with-account",
+        json!({
+            "payer": new_pubkey(),
+            "mintAuthority": new_pubkey(),
+            "mint": mint,
+            "decimals": 9
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(
+        instructions[0]["program_id"],
+        solana_sdk::system_program::ID.to_string()
+    );
+    assert_eq!(instructions[0]["accounts"][1]["pubkey"], mint);
+    assert_eq!(instructions[1]["program_id"], spl_token::ID.to_string());
+}
+
+#[tokio::test]
+async fn approve_batch_builds_one_instruction_per_delegate() {
+    let (status, body) = post_json(
+        "/token/approve/batch",
+        json!({
+            "source": new_pubkey(),
+            "mint": new_pubkey(),
+            "owner": new_pubkey(),
+            "decimals": 6,
+            "delegates": [
+                { "delegate": new_pubkey(), "amount": 100 },
+                { "delegate": new_pubkey(), "amount": 200 }
+            ]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["instructions"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn multi_instruction_endpoints_honour_serialized() {
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/sol/memo?serialized=true",
+        json!({ "from": from.to_string(), "to": to.to_string(), "lamports": 1000, "memo": "hi" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    let transfer = solana_sdk::system_instruction::transfer(&from, &to, 1000);
+    let serialized = base64::decode(instructions[0]["serialized_instruction"].as_str().unwrap());
+    let decoded: solana_sdk::instruction::Instruction =
+        bincode::deserialize(&serialized.unwrap()).unwrap();
+    assert_eq!(decoded, transfer);
+    assert!(instructions[1]["serialized_instruction"].is_string());
+}
+
+#[tokio::test]
+async fn approve_batch_reports_zero_amount_index() {
+    let (status, body) = post_json(
+        "/token/approve/batch",
+        json!({
+            "source": new_pubkey(),
+            "mint": new_pubkey(),
+            "owner": new_pubkey(),
+            "decimals": 6,
+            "delegates": [
+                { "delegate": new_pubkey(), "amount": 100 },
+                { "delegate": new_pubkey(), "amount": 0 }
+            ]
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_AMOUNT");
+    assert!(body["error"].as_str().unwrap().contains("index 1"));
+}
+
+#[tokio::test]
+async fn fit_check_suggests_split_for_oversized_batch() {
+    let program_id = new_pubkey();
+    let instructions: Vec<Value> = (0..10)
+        .map(|_| {
+            json!({
+                "program_id": program_id,
+                "accounts": [],
+                "data": base64::encode([7u8; 200])
+            })
+        })
+        .collect();
+
+    let (status, body) = post_json(
+        "/transaction/fit-check",
+        json!({ "instructions": instructions, "signer_count": 1 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["fits"], false);
+    assert!(body["data"]["estimatedSize"].as_u64().unwrap() > 1232);
+    assert_eq!(body["data"]["suggestedSplitIndex"], 5);
+    assert!(body["data"].get("oversizedInstructionIndex").is_none());
+}
+
+#[tokio::test]
+async fn fit_check_flags_instruction_too_large_to_split() {
+    let program_id = new_pubkey();
+    let instructions = json!([
+        { "program_id": program_id, "accounts": [], "data": base64::encode([7u8; 1300]) },
+        { "program_id": program_id, "accounts": [], "data": base64::encode([7u8; 10]) }
+    ]);
+
+    let (status, body) = post_json(
+        "/transaction/fit-check",
+        json!({ "instructions": instructions, "signer_count": 1 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["fits"], false);
+    assert_eq!(body["data"]["oversizedInstructionIndex"], 0);
+    assert!(body["data"].get("suggestedSplitIndex").is_none());
+}
+
+#[tokio::test]
+async fn rent_minimum_matches_rent_default() {
+    let (status, body) = post_json("/account/rent", json!({ "data_len": 82 })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["data"]["lamports"],
+        solana_sdk::rent::Rent::default().minimum_balance(82)
+    );
+}
+
+#[tokio::test]
+async fn rent_minimum_rejects_oversized_accounts() {
+    let (status, body) = post_json(
+        "/account/rent",
+        json!({ "data_len": 11 * 1024 * 1024 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn indexed_keypairs_are_stable_and_distinct() {
+    let seed = "000102030405060708090a0b0c0d0e0f";
+    let (status, first) = post_json(
+        "/keypair/indexed",
+        json!({ "masterSeed": seed, "index": 0 }),
+    )
+    .await;
+    let (_, second) = post_json(
+        "/keypair/indexed",
+        json!({ "masterSeed": seed, "index": 1 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        first["data"]["pubkey"],
+        "39LoiUgZejnJYJVhvvAnxkMooM1uJ15Hkiz2iXTUwF65"
+    );
+    assert_eq!(
+        second["data"]["pubkey"],
+        "4KdsMWuf8XrX7ck5EupYrU488tyWwpFoTd3JEY4hwG4R"
+    );
+}
+
+#[tokio::test]
+async fn indexed_keypair_rejects_short_master_seed() {
+    for seed in ["", "00010203", "000102030405060708090a0b0c0d0e"] {
+        let (status, body) = post_json(
+            "/keypair/indexed",
+            json!({ "masterSeed": seed, "index": 0 }),
+        )
+        .await;
+
+        assert_error(status, &body, "INVALID_INPUT");
+    }
+}
+
+#[tokio::test]
+async fn revoke_batch_signs_with_owner() {
+    let owner = new_pubkey();
+    let (status, body) = post_json(
+        "/token/revoke/batch",
+        json!({
+            "owner": owner,
+            "sources": [new_pubkey(), new_pubkey(), new_pubkey()]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 3);
+    for instruction in instructions {
+        assert_eq!(instruction["accounts"][1]["pubkey"], owner);
+        assert_eq!(instruction["accounts"][1]["is_signer"], true);
+    }
+}
+
+#[tokio::test]
+async fn revoke_batch_reports_bad_source_index() {
+    let (status, body) = post_json(
+        "/token/revoke/batch",
+        json!({ "owner": new_pubkey(), "sources": [new_pubkey(), "bad"] }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_PUBKEY");
+    assert!(body["error"].as_str().unwrap().contains("sources[1]"));
+}
+
+#[tokio::test]
+async fn benchmark_keypair_reports_positive_rate() {
+    let app = solana_axum_server::router(AppState::default(), None, true);
+    let request = Request::builder()
+        .uri("/benchmark/keypair?duration_ms=10")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["keypairs"].as_u64().unwrap() > 0);
+    assert!(body["data"]["keys_per_second"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn send_token_ata_derives_both_accounts() {
+    let sender = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/token/ata",
+        json!({
+            "sender": sender.to_string(),
+            "recipient": recipient.to_string(),
+            "mint": mint.to_string(),
+            "amount": 25,
+            "decimals": 6
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let sender_ata =
+        spl_associated_token_account::get_associated_token_address(&sender, &mint).to_string();
+    let recipient_ata =
+        spl_associated_token_account::get_associated_token_address(&recipient, &mint).to_string();
+    assert_eq!(body["data"]["source"], sender_ata);
+    assert_eq!(body["data"]["destination_ata"], recipient_ata);
+    let accounts = &body["data"]["accounts"];
+    assert_eq!(accounts[0]["pubkey"], sender_ata);
+    assert_eq!(accounts[2]["pubkey"], recipient_ata);
+    assert_eq!(accounts[3]["pubkey"], sender.to_string());
+}
+
+#[tokio::test]
+async fn verify_pda_linked_requires_derivation_and_signature() {
+    let program_id = Pubkey::new_unique();
+    let (pda, _) = Pubkey::find_program_address(&[b"vault", b"alice"], &program_id);
+    let signer = Keypair::new();
+    let signature = base64::encode(signer.sign_message(b"attest"));
+
+    let request = |pda: Pubkey, message: &str| {
+        json!({
+            "pda": pda.to_string(),
+            "programId": program_id.to_string(),
+            "seeds": ["vault", "alice"],
+            "message": message,
+            "pubkey": signer.pubkey().to_string(),
+            "signature": signature
+        })
+    };
+
+    let (status, both) = post_json("/message/verify/pda-linked", request(pda, "attest")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(both["data"]["valid"], true);
+
+    let (_, wrong_pda) =
+        post_json("/message/verify/pda-linked", request(Pubkey::new_unique(), "attest")).await;
+    assert_eq!(wrong_pda["data"]["pdaMatches"], false);
+    assert_eq!(wrong_pda["data"]["signatureValid"], true);
+    assert_eq!(wrong_pda["data"]["valid"], false);
+
+    let (_, wrong_message) =
+        post_json("/message/verify/pda-linked", request(pda, "tampered")).await;
+    assert_eq!(wrong_message["data"]["pdaMatches"], true);
+    assert_eq!(wrong_message["data"]["signatureValid"], false);
+    assert_eq!(wrong_message["data"]["valid"], false);
+}
+
+#[tokio::test]
+async fn build_sponsored_puts_fee_payer_in_slot_zero() {
+    let user = Pubkey::new_unique();
+    let fee_payer = Pubkey::new_unique();
+    let instruction =
+        solana_sdk::system_instruction::transfer(&user, &Pubkey::new_unique(), 1_000);
+
+    let (status, body) = post_json(
+        "/transaction/build-sponsored",
+        json!({
+            "instructions": [instruction_json(&instruction)],
+            "feePayer": fee_payer.to_string(),
+            "signers": [user.to_string()]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["feePayerIndex"], 0);
+    assert_eq!(body["data"]["signers"][0], fee_payer.to_string());
+    assert_eq!(body["data"]["signers"][1], user.to_string());
+
+    let bytes = base64::decode(body["data"]["transaction"].as_str().unwrap()).unwrap();
+    let transaction: solana_sdk::transaction::Transaction = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(transaction.message.account_keys[0], fee_payer);
+    assert_eq!(transaction.signatures.len(), 2);
+}
+
+#[tokio::test]
+async fn create_token_supports_token_2022() {
+    let (status, body) = post_json(
+        "/token/create",
+        json!({
+            "mintAuthority": new_pubkey(),
+            "mint": new_pubkey(),
+            "decimals": 6,
+            "program": "token-2022"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["program_id"], spl_token_2022::ID.to_string());
+}
+
+#[tokio::test]
+async fn mint_token_matches_each_programs_own_builder() {
+    let (mint, destination, authority) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let legacy =
+        spl_token::instruction::mint_to(&spl_token::ID, &mint, &destination, &authority, &[], 7)
+            .unwrap();
+
+    for program in ["token", "token-2022"] {
+        let (status, body) = post_json(
+            "/token/mint",
+            json!({
+                "mint": mint.to_string(),
+                "destination": destination.to_string(),
+                "authority": authority.to_string(),
+                "amount": 7,
+                "program": program
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["instruction_data"], base64::encode(&legacy.data));
+    }
+}
+
+#[tokio::test]
+async fn token_endpoints_reject_unknown_program() {
+    let (status, body) = post_json(
+        "/token/mint",
+        json!({
+            "mint": new_pubkey(),
+            "destination": new_pubkey(),
+            "authority": new_pubkey(),
+            "amount": 1,
+            "program": "token-2023"
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn validate_pubkey_distinguishes_wallets_and_pdas() {
+    let wallet = Keypair::new().pubkey();
+    let (pda, _) = Pubkey::find_program_address(&[b"seed"], &Pubkey::new_unique());
+
+    let (status, body) = post_json("/pubkey/validate", json!({ "pubkey": wallet.to_string() })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "valid": true, "on_curve": true }));
+
+    let (_, body) = post_json("/pubkey/validate", json!({ "pubkey": pda.to_string() })).await;
+    assert_eq!(body["data"], json!({ "valid": true, "on_curve": false }));
+
+    let (status, body) = post_json("/pubkey/validate", json!({ "pubkey": "nope" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "valid": false, "on_curve": false }));
+}
+
+#[tokio::test]
+async fn validate_flow_flags_decimal_mismatch() {
+    let (status, matched) = post_json(
+        "/token/validate-flow",
+        json!({ "createDecimals": 6, "mintDecimals": 6 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(matched["data"], json!({ "consistent": true }));
+
+    let (_, mismatched) = post_json(
+        "/token/validate-flow",
+        json!({ "createDecimals": 6, "mintDecimals": 9 }),
+    )
+    .await;
+    assert_eq!(mismatched["data"]["consistent"], false);
+    assert!(mismatched["data"]["hint"].is_string());
+}
+
+#[tokio::test]
+async fn build_transaction_uses_nonce_value_as_blockhash() {
+    let authority = Pubkey::new_unique();
+    let nonce_account = Pubkey::new_unique();
+    let nonce_value = solana_sdk::hash::Hash::new_unique();
+    let advance =
+        solana_sdk::system_instruction::advance_nonce_account(&nonce_account, &authority);
+
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": authority.to_string(),
+            "nonceValue": nonce_value.to_string(),
+            "instructions": [instruction_json(&advance)]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["message"].as_str().unwrap()).unwrap();
+    let message: solana_sdk::message::Message = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(message.recent_blockhash, nonce_value);
+}
+
+#[tokio::test]
+async fn build_transaction_prepends_advance_nonce() {
+    let authority = Pubkey::new_unique();
+    let nonce_account = Pubkey::new_unique();
+    let nonce_value = solana_sdk::hash::Hash::new_unique();
+    let transfer =
+        solana_sdk::system_instruction::transfer(&authority, &Pubkey::new_unique(), 1_000);
+
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": authority.to_string(),
+            "nonceValue": nonce_value.to_string(),
+            "nonce_account": nonce_account.to_string(),
+            "nonce_authority": authority.to_string(),
+            "instructions": [instruction_json(&transfer)]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["message"].as_str().unwrap()).unwrap();
+    let message: solana_sdk::message::Message = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(message.recent_blockhash, nonce_value);
+    assert_eq!(message.instructions.len(), 2);
+    let first = &message.instructions[0];
+    assert_eq!(
+        message.account_keys[first.program_id_index as usize],
+        solana_sdk::system_program::ID
+    );
+    assert_eq!(message.account_keys[first.accounts[0] as usize], nonce_account);
+
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": authority.to_string(),
+            "recent_blockhash": nonce_value.to_string(),
+            "nonce_account": nonce_account.to_string(),
+            "nonce_authority": authority.to_string(),
+            "instructions": []
+        }),
+    )
+    .await;
+    assert_error(status, &body, "MISSING_FIELD");
+}
+
+#[tokio::test]
+async fn wsol_ata_uses_native_mint() {
+    let owner = Pubkey::new_unique();
+    let (status, body) = post_json("/account/wsol-ata", json!({ "owner": owner.to_string() })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected = spl_associated_token_account::get_associated_token_address(
+        &owner,
+        &spl_token::native_mint::ID,
+    );
+    assert_eq!(body["data"]["ata"], expected.to_string());
+    assert_eq!(body["data"]["mint"], spl_token::native_mint::ID.to_string());
+}
+
+#[tokio::test]
+async fn instruction_data_can_be_hex_encoded() {
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let body = json!({ "from": from.to_string(), "to": to.to_string(), "lamports": 42 });
+
+    let (status, hex_body) = post_json("/send/sol?encoding=hex", body.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let expected = solana_sdk::system_instruction::transfer(&from, &to, 42).data;
+    let expected_hex: String = expected.iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(hex_body["data"]["instruction_data"], expected_hex);
+
+    let (status, url_body) = post_json("/send/sol?encoding=base64url", body.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let url_safe = url_body["data"]["instruction_data"].as_str().unwrap();
+    assert!(!url_safe.contains('='));
+    {
+        use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+        assert_eq!(URL_SAFE_NO_PAD.decode(url_safe).unwrap(), expected);
+    }
+
+    let (status, bad) = post_json("/send/sol?encoding=base32", body).await;
+    assert_error(status, &bad, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn multi_instruction_endpoints_honour_hex_encoding() {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    let body = json!({ "compute_unit_limit": 200_000 });
+    let (status, hex_body) =
+        post_json("/instructions/compute-budget?encoding=hex", body.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let expected = ComputeBudgetInstruction::set_compute_unit_limit(200_000).data;
+    let expected_hex: String = expected.iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(hex_body["data"]["instructions"][0]["instruction_data"], expected_hex);
+
+    let (status, bad) = post_json("/instructions/compute-budget?encoding=base32", body).await;
+    assert_error(status, &bad, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn sign_batch_signs_every_message() {
+    let keypair = Keypair::new();
+    let (status, body) = post_json(
+        "/message/sign-batch",
+        json!({
+            "secret": bs58::encode(keypair.to_bytes()).into_string(),
+            "messages": ["one", "two", "three"]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let signatures = body["data"]["signatures"].as_array().unwrap();
+    assert_eq!(signatures.len(), 3);
+    for (entry, message) in signatures.iter().zip(["one", "two", "three"]) {
+        assert_eq!(entry["message"], message);
+        let expected = base64::encode(keypair.sign_message(message.as_bytes()));
+        assert_eq!(entry["signature"], expected);
+    }
+}
+
+#[tokio::test]
+async fn build_transaction_base64url_matches_base64_bytes() {
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+    let fee_payer = Pubkey::new_unique();
+    let instruction = solana_sdk::system_instruction::transfer(&fee_payer, &Pubkey::new_unique(), 7);
+    let body = json!({
+        "fee_payer": fee_payer.to_string(),
+        "recent_blockhash": solana_sdk::hash::Hash::new_unique().to_string(),
+        "instructions": [instruction_json(&instruction)]
+    });
+
+    let (_, standard) = post_json("/transaction/build", body.clone()).await;
+    let (status, url_safe) = post_json("/transaction/build?encoding=base64url", body).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let url_safe = url_safe["data"]["message"].as_str().unwrap();
+    assert!(!url_safe.contains(['+', '/', '=']));
+    assert_eq!(
+        URL_SAFE_NO_PAD.decode(url_safe).unwrap(),
+        base64::decode(standard["data"]["message"].as_str().unwrap()).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn amounts_accept_numeric_strings() {
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": from.to_string(), "to": to.to_string(), "lamports": "18446744073709551615" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected = solana_sdk::system_instruction::transfer(&from, &to, u64::MAX).data;
+    assert_eq!(body["data"]["instruction_data"], base64::encode(expected));
+}
+
+#[tokio::test]
+async fn amounts_reject_non_numeric_and_overflowing_strings() {
+    for amount in ["12abc", "18446744073709551616", "-1"] {
+        let (status, body) = post_json(
+            "/token/mint",
+            json!({
+                "mint": new_pubkey(),
+                "destination": new_pubkey(),
+                "authority": new_pubkey(),
+                "amount": amount
+            }),
+        )
+        .await;
+
+        assert_error(status, &body, "INVALID_INPUT");
+    }
+}
+
+#[tokio::test]
+async fn verify_threshold_counts_distinct_valid_signers() {
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let entries: Vec<Value> = signers
+        .iter()
+        .enumerate()
+        .map(|(i, signer)| {
+            // The third signer signs a different message.
+            let message: &[u8] = if i == 2 { b"other" } else { b"proposal #7" };
+            json!({
+                "pubkey": signer.pubkey().to_string(),
+                "signature": base64::encode(signer.sign_message(message))
+            })
+        })
+        .collect();
+
+    let (status, body) = post_json(
+        "/message/verify/threshold",
+        json!({ "message": "proposal #7", "threshold": 2, "signatures": entries }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["met"], true);
+    assert_eq!(body["data"]["validCount"], 2);
+    assert_eq!(
+        body["data"]["validPubkeys"],
+        json!([signers[0].pubkey().to_string(), signers[1].pubkey().to_string()])
+    );
+}
+
+#[tokio::test]
+async fn transfer_token_builds_unchecked_transfer() {
+    let source = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/token/transfer",
+        json!({
+            "source": source.to_string(),
+            "destination": destination.to_string(),
+            "owner": owner.to_string(),
+            "amount": 500
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected =
+        spl_token::instruction::transfer(&spl_token::ID, &source, &destination, &owner, &[], 500)
+            .unwrap();
+    assert_eq!(body["data"]["instruction_data"], base64::encode(expected.data));
+    assert_eq!(body["data"]["accounts"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn decode_checked_round_trips_transfer_checked() {
+    let owner = new_pubkey();
+    let (_, built) = post_json(
+        "/send/token",
+        json!({
+            "destination": new_pubkey(),
+            "mint": new_pubkey(),
+            "owner": owner,
+            "amount": 123_456
+        }),
+    )
+    .await;
+
+    let (status, body) = post_json(
+        "/token/decode-checked",
+        json!({ "data": built["data"]["instruction_data"] }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["instruction"], "TransferChecked");
+    assert_eq!(body["data"]["amount"], 123_456);
+    assert_eq!(body["data"]["decimals"], 6);
+}
+
+#[tokio::test]
+async fn decode_checked_rejects_unchecked_instructions() {
+    let data = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &[],
+        1,
+    )
+    .unwrap()
+    .data;
+
+    let (status, body) =
+        post_json("/token/decode-checked", json!({ "data": base64::encode(data) })).await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn prepare_create_token_references_returned_mint() {
+    let (status, body) = post_json(
+        "/token/prepare-create",
+        json!({ "mintAuthority": new_pubkey(), "decimals": 6 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let secret = bs58::decode(body["data"]["mint"]["secret"].as_str().unwrap())
+        .into_vec()
+        .unwrap();
+    let mint = Keypair::from_bytes(&secret).unwrap().pubkey().to_string();
+    assert_eq!(body["data"]["mint"]["pubkey"], mint);
+
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[0]["accounts"][1]["pubkey"], mint);
+    assert_eq!(instructions[1]["accounts"][0]["pubkey"], mint);
+    // initialize_mint2 doesn't take the rent sysvar.
+    assert_eq!(instructions[1]["accounts"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn responses_carry_response_time_header() {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/keypair")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app().oneshot(request).await.unwrap();
+
+    let header = response.headers().get("x-response-time-ms").unwrap();
+    assert!(header.to_str().unwrap().parse::<u64>().is_ok());
+}
+
+#[tokio::test]
+async fn derive_pda_matches_find_program_address() {
+    let program_id = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (expected, bump) =
+        Pubkey::find_program_address(&[b"escrow", owner.as_ref()], &program_id);
+
+    let (status, utf8) = post_json(
+        "/keypair/derive-pda",
+        json!({ "program_id": program_id.to_string(), "seeds": ["escrow"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        utf8["data"]["pda"],
+        Pubkey::find_program_address(&[b"escrow"], &program_id).0.to_string()
+    );
+
+    let (status, mixed) = post_json(
+        "/keypair/derive-pda",
+        json!({
+            "program_id": program_id.to_string(),
+            "seeds": [bs58::encode(b"escrow").into_string(), owner.to_string()],
+            "seed_encoding": "base58"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(mixed["data"]["pda"], expected.to_string());
+    assert_eq!(mixed["data"]["bump"], bump);
+}
+
+#[tokio::test]
+async fn derive_pda_rejects_long_seeds() {
+    let (status, body) = post_json(
+        "/keypair/derive-pda",
+        json!({ "program_id": new_pubkey(), "seeds": ["x".repeat(33)] }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+async fn blockhash_status_with(
+    rpc: Option<solana_client::nonblocking::rpc_client::RpcClient>,
+) -> (StatusCode, Value) {
+    let fee_payer = Pubkey::new_unique();
+    let instruction = solana_sdk::system_instruction::transfer(&fee_payer, &Pubkey::new_unique(), 1);
+    let message = solana_sdk::message::Message::new_with_blockhash(
+        &[instruction],
+        Some(&fee_payer),
+        &solana_sdk::hash::Hash::new_unique(),
+    );
+    let transaction = solana_sdk::transaction::Transaction::new_unsigned(message);
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/transaction/blockhash-status")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "transaction": base64::encode(bincode::serialize(&transaction).unwrap()) })
+                .to_string(),
+        ))
+        .unwrap();
+
+    let response = solana_axum_server::app_with_rpc(rpc.map(std::sync::Arc::new))
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn blockhash_status_requires_rpc() {
+    let (status, body) = blockhash_status_with(None).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "RPC_NOT_CONFIGURED");
+}
+
+#[tokio::test]
+async fn blockhash_status_reports_expired_blockhash() {
+    use solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest};
+
+    let mut mocks = std::collections::HashMap::new();
+    mocks.insert(
+        RpcRequest::IsBlockhashValid,
+        json!({ "context": { "slot": 1 }, "value": false }),
+    );
+    let rpc = RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks);
+
+    let (status, body) = blockhash_status_with(Some(rpc)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["valid"], false);
+    assert!(body["data"].get("estimatedRemainingSlots").is_none());
+}
+
+#[tokio::test]
+async fn set_authority_batch_rotates_each_mint() {
+    let current = new_pubkey();
+    let (status, body) = post_json(
+        "/token/set-authority/batch",
+        json!({
+            "currentAuthority": current,
+            "newAuthority": new_pubkey(),
+            "authorityType": "mint",
+            "accounts": [new_pubkey(), new_pubkey(), new_pubkey()]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 3);
+    for instruction in instructions {
+        assert_eq!(instruction["accounts"][1]["pubkey"], current);
+        assert_eq!(instruction["accounts"][1]["is_signer"], true);
+    }
+}
+
+#[tokio::test]
+async fn set_authority_batch_rejects_unknown_type() {
+    let (status, body) = post_json(
+        "/token/set-authority/batch",
+        json!({
+            "currentAuthority": new_pubkey(),
+            "newAuthority": new_pubkey(),
+            "authorityType": "supreme",
+            "accounts": [new_pubkey()]
+        }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn set_authority_can_disable_freeze_authority() {
+    let mint = new_pubkey();
+    let current = new_pubkey();
+    let (status, body) = post_json(
+        "/token/set-authority",
+        json!({
+            "account": mint,
+            "current_authority": current,
+            "new_authority": null,
+            "authority_type": "freeze"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], mint);
+    assert_eq!(body["data"]["accounts"][1]["pubkey"], current);
+
+    let data = base64::decode(body["data"]["instruction_data"].as_str().unwrap()).unwrap();
+    let expected = spl_token::instruction::set_authority(
+        &spl_token::ID,
+        &mint.parse().unwrap(),
+        None,
+        spl_token::instruction::AuthorityType::FreezeAccount,
+        &current.parse().unwrap(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(data, expected.data);
+}
+
+#[tokio::test]
+async fn sign_timestamped_embeds_timestamp_and_verifies() {
+    let keypair = Keypair::new();
+    let (status, body) = post_json(
+        "/message/sign-timestamped",
+        json!({
+            "secret": bs58::encode(keypair.to_bytes()).into_string(),
+            "payload": "login"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let timestamp = body["data"]["timestamp"].as_u64().unwrap();
+    assert_eq!(body["data"]["pubkey"], keypair.pubkey().to_string());
+    assert_eq!(body["data"]["signedMessage"], format!("{}|login", timestamp));
+
+    let (status, verified) = post_json(
+        "/message/verify-timestamped",
+        json!({
+            "pubkey": keypair.pubkey().to_string(),
+            "signature": body["data"]["signature"],
+            "timestamp": timestamp,
+            "payload": "login"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(verified["data"]["valid"], true);
+    assert_eq!(verified["data"]["fresh"], true);
+}
+
+#[tokio::test]
+async fn verify_timestamped_rejects_stale_signature() {
+    let keypair = Keypair::new();
+    let timestamp = 1_000_000u64;
+    let signature = keypair.sign_message(format!("{}|login", timestamp).as_bytes());
+    let (status, body) = post_json(
+        "/message/verify-timestamped",
+        json!({
+            "pubkey": keypair.pubkey().to_string(),
+            "signature": base64::encode(signature),
+            "timestamp": timestamp,
+            "payload": "login"
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["signatureValid"], true);
+    assert_eq!(body["data"]["fresh"], false);
+    assert_eq!(body["data"]["valid"], false);
+}
+
+#[tokio::test]
+async fn validate_signature_accepts_base58_signature() {
+    let signature = Keypair::new().sign_message(b"hello");
+    let (status, body) = post_json(
+        "/signature/validate",
+        json!({ "signature": signature.to_string() }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "valid": true, "length": 64 }));
+}
+
+#[tokio::test]
+async fn validate_signature_rejects_base64_input() {
+    let signature = Keypair::new().sign_message(b"hello");
+    let (status, body) = post_json(
+        "/signature/validate",
+        json!({ "signature": base64::encode(signature) }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["valid"], false);
+    assert_eq!(body["data"]["reason"], "not_base58");
+    assert_eq!(body["data"]["length"], Value::Null);
+}
+
+#[tokio::test]
+async fn validate_signature_rejects_wrong_length() {
+    let (status, body) = post_json(
+        "/signature/validate",
+        json!({ "signature": bs58::encode([7u8; 32]).into_string() }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["data"],
+        json!({ "valid": false, "length": 32, "reason": "wrong_length" })
+    );
+}
+
+#[tokio::test]
+async fn malformed_json_uses_error_envelope() {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/send/sol")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{\"from\": "))
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn malformed_query_uses_error_envelope() {
+    let (status, body) = post_json(
+        "/send/sol?as_array=maybe",
+        json!({ "from": new_pubkey(), "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn missing_json_field_reports_missing_field() {
+    let (status, body) = post_json("/send/sol", json!({ "from": new_pubkey(), "lamports": 1 })).await;
+
+    assert_error(status, &body, "MISSING_FIELD");
+    assert!(body["error"].as_str().unwrap().contains("`to`"));
+}
+
+#[tokio::test]
+async fn amount_convert_round_trips_between_units() {
+    let (status, body) = post_json(
+        "/token/amount/convert",
+        json!({ "ui_amount": 1.1, "decimals": 6 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "raw": 1_100_000 }));
+
+    let (status, body) = post_json(
+        "/token/amount/convert",
+        json!({ "raw": 1_100_000, "decimals": 6, "direction": "to_ui" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "ui_amount": 1.1, "ui_amount_string": "1.1" }));
+}
+
+#[tokio::test]
+async fn amount_convert_to_ui_is_exact_for_u64_max() {
+    let (status, body) = post_json(
+        "/token/amount/convert",
+        json!({ "raw": u64::MAX, "decimals": 9, "direction": "to_ui" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["ui_amount_string"], "18446744073.709551615");
+}
+
+#[tokio::test]
+async fn amount_convert_rejects_negative_and_overflowing_amounts() {
+    for ui_amount in [-1.0, 1e19] {
+        let (status, body) = post_json(
+            "/token/amount/convert",
+            json!({ "ui_amount": ui_amount, "decimals": 9 }),
+        )
+        .await;
+
+        assert_error(status, &body, "INVALID_AMOUNT");
+    }
+}
+
+#[tokio::test]
+async fn from_responses_assembles_create_token_and_send_sol() {
+    let authority = Pubkey::new_unique();
+    let (_, create) = post_json(
+        "/token/create",
+        json!({ "mintAuthority": authority.to_string(), "mint": new_pubkey(), "decimals": 6 }),
+    )
+    .await;
+    let (_, send) = post_json(
+        "/send/sol",
+        json!({ "from": authority.to_string(), "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    // The simplified account shape from older responses must still work.
+    let legacy = json!({
+        "program_id": send["data"]["program_id"],
+        "accounts": send["data"]["accounts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|meta| json!({ "pubkey": meta["pubkey"], "isSigner": meta["is_signer"] }))
+            .collect::<Vec<_>>(),
+        "instruction_data": send["data"]["instruction_data"]
+    });
+
+    let blockhash = solana_sdk::hash::Hash::new_unique();
+    let (status, body) = post_json(
+        "/transaction/from-responses",
+        json!({
+            "feePayer": authority.to_string(),
+            "recentBlockhash": blockhash.to_string(),
+            "responses": [create["data"], legacy]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["transaction"].as_str().unwrap()).unwrap();
+    let transaction: solana_sdk::transaction::Transaction = bincode::deserialize(&bytes).unwrap();
+    let message = &transaction.message;
+    assert_eq!(message.account_keys[0], authority);
+    assert_eq!(message.recent_blockhash, blockhash);
+    assert_eq!(message.instructions.len(), 2);
+    assert_eq!(
+        message.account_keys[message.instructions[0].program_id_index as usize],
+        spl_token::ID
+    );
+    assert_eq!(
+        message.account_keys[message.instructions[1].program_id_index as usize],
+        solana_sdk::system_program::ID
+    );
+}
+
+#[tokio::test]
+async fn from_responses_requires_recent_blockhash() {
+    let (_, send) = post_json(
+        "/send/sol",
+        json!({ "from": new_pubkey(), "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    let (status, body) = post_json(
+        "/transaction/from-responses",
+        json!({ "feePayer": new_pubkey(), "responses": [send["data"]] }),
+    )
+    .await;
+
+    assert_error(status, &body, "MISSING_FIELD");
+}
+
+#[tokio::test]
+async fn metrics_counts_requests_and_errors_per_endpoint() {
+    let app = app();
+    for body in [json!({ "pubkey": new_pubkey() }), json!({ "nope": true })] {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/pubkey/validate")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap();
+    }
+
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri("/metrics")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+    assert!(text.contains("http_requests_total{method=\"POST\",path=\"/pubkey/validate\"} 2"));
+    assert!(text.contains("http_request_errors_total{method=\"POST\",path=\"/pubkey/validate\"} 1"));
+}
+
+#[test]
+fn rate_limiter_throttles_each_client_separately() {
+    let limiter = solana_axum_server::state::RateLimiter::new(2);
+
+    assert!(limiter.check("10.0.0.1").is_ok());
+    assert!(limiter.check("10.0.0.1").is_ok());
+    let retry_after = limiter.check("10.0.0.1").unwrap_err();
+    assert!(retry_after > 0 && retry_after <= 30);
+
+    assert!(limiter.check("10.0.0.2").is_ok());
+}
+
+#[tokio::test]
+async fn rate_limit_ignores_spoofed_forwarded_for_entries() {
+    let state = AppState {
+        rate_limiter: Some(std::sync::Arc::new(solana_axum_server::state::RateLimiter::new(1))),
+        ..AppState::default()
+    };
+    let app = axum::Router::new()
+        .route("/ping", axum::routing::get(|| async { "pong" }))
+        .layer(axum::middleware::from_fn_with_state(state, middleware::rate_limit));
+
+    let mut statuses = Vec::new();
+    for spoofed in ["1.1.1.1", "2.2.2.2"] {
+        let request = Request::builder()
+            .uri("/ping")
+            .header("x-forwarded-for", format!("{spoofed}, 10.0.0.9"))
+            .body(Body::empty())
+            .unwrap();
+        statuses.push(app.clone().oneshot(request).await.unwrap().status());
+    }
+
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::TOO_MANY_REQUESTS]);
+}
+
+#[tokio::test]
+async fn verify_batch_reports_each_item_in_order() {
+    let keypair = Keypair::new();
+    let pubkey = keypair.pubkey().to_string();
+    let signature = base64::encode(keypair.sign_message(b"hello"));
+
+    let (status, body) = post_json(
+        "/message/verify-batch",
+        json!({
+            "items": [
+                { "message": "hello", "signature": signature, "pubkey": pubkey },
+                { "message": "tampered", "signature": signature, "pubkey": pubkey },
+                { "message": "hello", "signature": signature, "pubkey": "bad" }
+            ]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let results = body["data"]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["valid"], true);
+    assert_eq!(results[1]["valid"], false);
+    assert!(results[1].get("error").is_none());
+    assert_eq!(results[2]["valid"], false);
+    assert_eq!(results[2]["error"], "Invalid pubkey address: must decode to 32 bytes, got 2");
+}
+
+#[tokio::test]
+async fn envelope_false_returns_bare_data() {
+    let from = new_pubkey();
+    let (status, body) = post_json(
+        "/send/sol?envelope=false",
+        json!({ "from": from, "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("success").is_none());
+    assert_eq!(body["accounts"][0]["pubkey"], from);
+
+    let (status, body) = post_json(
+        "/send/sol?envelope=false",
+        json!({ "from": "bad", "to": new_pubkey(), "lamports": 5000 }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_PUBKEY");
+}
+
+#[tokio::test]
+async fn recover_pubkey_matches_secret() {
+    let keypair = Keypair::new();
+    let (status, body) = post_json(
+        "/keypair/pubkey",
+        json!({ "secret": bs58::encode(keypair.to_bytes()).into_string() }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({ "pubkey": keypair.pubkey().to_string() }));
+
+    let (status, body) = post_json("/keypair/pubkey", json!({ "secret": "0OIl" })).await;
+    assert_error(status, &body, "INVALID_SECRET_KEY");
+}
+
+#[tokio::test]
+async fn send_sol_can_return_serialized_instruction() {
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/sol?serialized=true",
+        json!({ "from": from.to_string(), "to": to.to_string(), "lamports": 42 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["serialized_instruction"].as_str().unwrap()).unwrap();
+    let instruction: solana_sdk::instruction::Instruction = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(instruction, solana_sdk::system_instruction::transfer(&from, &to, 42));
+}
+
+#[tokio::test]
+async fn create_token_with_extensions_orders_mint_init_last() {
+    let mint = new_pubkey();
+    let (status, body) = post_json(
+        "/token/create/extensions",
+        json!({
+            "mintAuthority": new_pubkey(),
+            "mint": mint,
+            "decimals": 6,
+            "extensions": [
+                { "type": "transfer_fee", "basis_points": 50, "max_fee": 1000 },
+                { "type": "non_transferable" }
+            ]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 3);
+    for instruction in instructions {
+        assert_eq!(instruction["program_id"], spl_token_2022::ID.to_string());
+        assert_eq!(instruction["accounts"][0]["pubkey"], mint);
+    }
+    let last = base64::decode(instructions[2]["instruction_data"].as_str().unwrap()).unwrap();
+    assert!(matches!(
+        spl_token_2022::instruction::TokenInstruction::unpack(&last).unwrap(),
+        spl_token_2022::instruction::TokenInstruction::InitializeMint2 { decimals: 6, .. }
+    ));
+    assert!(body["data"]["mint_space"].as_u64().unwrap() > 82);
+}
+
+#[tokio::test]
+async fn create_token_with_extensions_rejects_bad_parameters() {
+    for extension in [
+        json!({ "type": "transfer_fee", "basis_points": 10_001, "max_fee": 1 }),
+        json!({ "type": "confidential_everything" }),
+    ] {
+        let (status, body) = post_json(
+            "/token/create/extensions",
+            json!({
+                "mintAuthority": new_pubkey(),
+                "mint": new_pubkey(),
+                "decimals": 6,
+                "extensions": [extension]
+            }),
+        )
+        .await;
+
+        assert_error(status, &body, "INVALID_INPUT");
+    }
+}
+
+#[tokio::test]
+async fn request_id_is_echoed_or_generated() {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/keypair")
+        .header("x-request-id", "trace-123")
+        .body(Body::empty())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    assert_eq!(response.headers().get("x-request-id").unwrap(), "trace-123");
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/keypair")
+        .body(Body::empty())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    let generated = response.headers().get("x-request-id").unwrap().to_str().unwrap();
+    assert_eq!(generated.len(), 36);
+}
+
+#[tokio::test]
+async fn send_sol_with_memo_adds_signed_memo() {
+    let from = new_pubkey();
+    let (status, body) = post_json(
+        "/send/sol/memo",
+        json!({ "from": from, "to": new_pubkey(), "lamports": 1000, "memo": "deposit 42" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[1]["program_id"], spl_memo::ID.to_string());
+    assert_eq!(instructions[1]["accounts"][0]["pubkey"], from);
+    assert_eq!(instructions[1]["accounts"][0]["is_signer"], true);
+    assert_eq!(
+        base64::decode(instructions[1]["instruction_data"].as_str().unwrap()).unwrap(),
+        b"deposit 42"
+    );
+
+    let (status, body) = post_json(
+        "/send/sol/memo",
+        json!({ "from": from, "to": new_pubkey(), "lamports": 1000, "memo": "x".repeat(567) }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn mint_token_rejects_zero_and_amounts_above_ceiling() {
+    let state = AppState {
+        max_mint_amount: Some(1_000_000_000_000),
+        ..AppState::default()
+    };
+    let app = solana_axum_server::router(state, None, false);
+
+    for amount in [0u64, 1_000_000_000_001] {
+        let body = json!({
+            "mint": new_pubkey(),
+            "destination": new_pubkey(),
+            "authority": new_pubkey(),
+            "amount": amount
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/token/mint")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_error(status, &body, "INVALID_AMOUNT");
+    }
+}
+
+#[tokio::test]
+async fn decode_transaction_round_trips_built_message() {
+    let fee_payer = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let blockhash = solana_sdk::hash::Hash::new_unique();
+    let transfer = solana_sdk::system_instruction::transfer(&fee_payer, &to, 1_000);
+
+    let (_, built) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": fee_payer.to_string(),
+            "recent_blockhash": blockhash.to_string(),
+            "instructions": [instruction_json(&transfer)]
+        }),
+    )
+    .await;
+
+    let (status, body) = post_json(
+        "/transaction/decode",
+        json!({ "message": built["data"]["message"] }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["version"], "legacy");
+    assert_eq!(body["data"]["fee_payer"], fee_payer.to_string());
+    assert_eq!(body["data"]["recent_blockhash"], blockhash.to_string());
+    assert_eq!(body["data"]["instructions"], json!([instruction_json(&transfer)]));
+}
+
+#[tokio::test]
+async fn decode_transaction_reports_garbage() {
+    let (status, body) = post_json(
+        "/transaction/decode",
+        json!({ "transaction": base64::encode([1u8, 2, 3]) }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_TRANSACTION");
+}
+
+#[tokio::test]
+async fn generate_mnemonic_honours_word_count() {
+    for words in [12, 24] {
+        let (status, body) = post_json("/keypair/mnemonic", json!({ "words": words })).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let phrase = body["data"]["mnemonic"].as_str().unwrap();
+        assert_eq!(phrase.split_whitespace().count(), words);
+        let secret = bs58::decode(body["data"]["secret"].as_str().unwrap())
+            .into_vec()
+            .unwrap();
+        let keypair = Keypair::from_bytes(&secret).unwrap();
+        assert_eq!(body["data"]["pubkey"], keypair.pubkey().to_string());
+    }
+
+    let (status, body) = post_json("/keypair/mnemonic", json!({ "words": 15 })).await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn keypair_from_mnemonic_restores_generated_keypair() {
+    let (_, generated) = post_json("/keypair/mnemonic", json!({ "words": 12 })).await;
+    let phrase = generated["data"]["mnemonic"].as_str().unwrap();
+
+    let (status, body) = post_json(
+        "/keypair/from-mnemonic",
+        json!({ "mnemonic": format!("  {}\n", phrase.to_uppercase()) }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["pubkey"], generated["data"]["pubkey"]);
+    assert_eq!(body["data"]["secret"], generated["data"]["secret"]);
+
+    let (_, other_path) = post_json(
+        "/keypair/from-mnemonic",
+        json!({ "mnemonic": phrase, "derivation_path": "m/44'/501'/1'/0'" }),
+    )
+    .await;
+    assert_ne!(other_path["data"]["pubkey"], generated["data"]["pubkey"]);
+}
+
+#[tokio::test]
+async fn keypair_from_mnemonic_distinguishes_checksum_and_word_count() {
+    let abandon = vec!["abandon"; 12].join(" ");
+    let (status, body) = post_json("/keypair/from-mnemonic", json!({ "mnemonic": abandon })).await;
+    assert_error(status, &body, "INVALID_INPUT");
+    assert!(body["error"].as_str().unwrap().contains("checksum"));
+
+    let short = vec!["abandon"; 5].join(" ");
+    let (status, body) = post_json("/keypair/from-mnemonic", json!({ "mnemonic": short })).await;
+    assert_error(status, &body, "INVALID_INPUT");
+    assert!(body["error"].as_str().unwrap().contains("5 words"));
+}
+
+#[tokio::test]
+async fn panics_become_internal_error_envelope() {
+    let response = solana_axum_server::middleware::handle_panic(Box::new("boom"));
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        body,
+        json!({ "success": false, "error": "Internal server error", "code": "INTERNAL" })
+    );
+}
+
+#[tokio::test]
+async fn panicking_route_returns_envelope_through_full_stack() {
+    async fn boom() -> &'static str {
+        panic!("boom")
+    }
+    let routes = axum::Router::new().route("/panic", axum::routing::get(boom));
+    let app = solana_axum_server::with_layers(routes, AppState::default(), None);
+
+    let request = Request::builder().uri("/panic").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.headers().contains_key(middleware::REQUEST_ID_HEADER));
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], "INTERNAL");
+}
+
+#[tokio::test]
+async fn fees_estimate_adds_priority_fee_to_base_fee() {
+    let (status, body) = post_json(
+        "/fees/estimate",
+        json!({
+            "instruction_count": 2,
+            "signature_count": 2,
+            "compute_unit_price": 1_500,
+            "compute_unit_limit": 300_001
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    // 300_001 * 1_500 micro-lamports = 450.0015 lamports, rounded up.
+    assert_eq!(
+        body["data"],
+        json!({
+            "lamports": 10_451,
+            "breakdown": {
+                "base_fee": 10_000,
+                "priority_fee": 451,
+                "compute_unit_limit": 300_001,
+                "compute_unit_price": 1_500
+            }
+        })
+    );
+
+    let (_, body) = post_json(
+        "/fees/estimate",
+        json!({ "instruction_count": 3, "signature_count": 1 }),
+    )
+    .await;
+    assert_eq!(body["data"]["lamports"], 5_000);
+    assert_eq!(body["data"]["breakdown"]["compute_unit_limit"], 600_000);
+}
+
+#[tokio::test]
+async fn compute_budget_returns_limit_then_price() {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    let (status, body) = post_json(
+        "/instructions/compute-budget",
+        json!({ "compute_unit_limit": 300_000, "compute_unit_price": 1_500 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 2);
+    let limit = ComputeBudgetInstruction::set_compute_unit_limit(300_000);
+    let price = ComputeBudgetInstruction::set_compute_unit_price(1_500);
+    assert_eq!(instructions[0]["program_id"], limit.program_id.to_string());
+    assert_eq!(instructions[0]["instruction_data"], base64::encode(&limit.data));
+    assert_eq!(instructions[1]["instruction_data"], base64::encode(&price.data));
+
+    let (status, body) = post_json("/instructions/compute-budget", json!({})).await;
+    assert_error(status, &body, "MISSING_FIELD");
+}
+
+#[tokio::test]
+async fn build_transaction_puts_separate_fee_payer_first() {
+    let fee_payer = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let transfer =
+        solana_sdk::system_instruction::transfer(&authority, &Pubkey::new_unique(), 1_000);
+
+    let (status, body) = post_json(
+        "/transaction/build",
+        json!({
+            "fee_payer": fee_payer.to_string(),
+            "recent_blockhash": solana_sdk::hash::Hash::new_unique().to_string(),
+            "instructions": [instruction_json(&transfer)]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let bytes = base64::decode(body["data"]["message"].as_str().unwrap()).unwrap();
+    let message: solana_sdk::message::Message = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(message.account_keys[0], fee_payer);
+    assert!(message.is_signer(0));
+    assert!(message.is_writable(0));
+    assert_eq!(message.account_keys[1], authority);
+    assert_eq!(message.header.num_required_signatures, 2);
+}
+
+#[tokio::test]
+async fn create_token_new_mint_wires_generated_keypair() {
+    let payer = new_pubkey();
+    let (status, body) = post_json(
+        "/token/create/new-mint",
+        json!({ "mintAuthority": new_pubkey(), "decimals": 9, "payer": payer }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let secret = bs58::decode(body["data"]["secret"].as_str().unwrap())
+        .into_vec()
+        .unwrap();
+    let mint = Keypair::from_bytes(&secret).unwrap().pubkey().to_string();
+    assert_eq!(body["data"]["pubkey"], mint);
+
+    let instructions = body["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions[0]["accounts"][0]["pubkey"], payer);
+    assert_eq!(instructions[0]["accounts"][1]["pubkey"], mint);
+    assert_eq!(instructions[1]["accounts"][0]["pubkey"], mint);
+    assert_eq!(
+        instructions[1]["accounts"][1]["pubkey"],
+        solana_sdk::sysvar::rent::ID.to_string()
+    );
+}
+
+#[tokio::test]
+async fn unknown_fields_are_rejected_by_name() {
+    let (status, body) = post_json(
+        "/token/create",
+        json!({ "mintAutority": new_pubkey(), "mint": new_pubkey(), "decimals": 6 }),
+    )
+    .await;
+
+    assert_error(status, &body, "INVALID_INPUT");
+    assert!(body["error"].as_str().unwrap().contains("unknown field `mintAutority`"));
+}
+
+#[tokio::test]
+async fn version_reports_package_version() {
+    let (status, body) = send(Method::GET, "/version", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["data"]["build_time"].is_string());
+}
+
+#[tokio::test]
+async fn send_token_batch_honours_base64url_encoding() {
+    let (status, body) = post_json(
+        "/send/token/batch?encoding=base64url",
+        json!({
+            "owner": new_pubkey(),
+            "mint": new_pubkey(),
+            "decimals": 6,
+            "entries": [{ "destination": new_pubkey(), "amount": 100 }]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let data = body["data"]["results"][0]["instruction_data"].as_str().unwrap();
+    assert!(!data.contains('=') && !data.contains('+') && !data.contains('/'));
+    {
+        use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+        let bytes = URL_SAFE_NO_PAD.decode(data).unwrap();
+        assert_eq!(bytes[0], 12); // TransferChecked
+        assert_eq!(bytes[1..9], 100u64.to_le_bytes());
+    }
+}
+
+#[tokio::test]
+async fn send_token_batch_reports_bad_entries_inline() {
+    let owner = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let good = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/token/batch",
+        json!({
+            "owner": owner.to_string(),
+            "mint": mint.to_string(),
+            "decimals": 6,
+            "entries": [
+                { "destination": good.to_string(), "amount": 100 },
+                { "destination": "bad", "amount": 100 },
+                { "destination": new_pubkey(), "amount": 0 }
+            ]
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let source = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+    let good_ata = spl_associated_token_account::get_associated_token_address(&good, &mint);
+
+    let results = body["data"]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["source"], source.to_string());
+    assert_eq!(results[0]["destination_ata"], good_ata.to_string());
+    assert_eq!(results[0]["accounts"][0]["pubkey"], source.to_string());
+    assert_eq!(results[0]["accounts"][2]["pubkey"], good_ata.to_string());
+    assert!(results[0].get("error").is_none());
+    assert!(results[1].get("destination_ata").is_none());
+    assert_eq!(
+        results[1]["error"],
+        "Invalid entries[1].destination address: must decode to 32 bytes, got 2"
+    );
+    assert!(results[2]["error"].as_str().unwrap().contains("greater than zero"));
+}
+
+#[tokio::test]
+async fn token_transfer_endpoints_report_the_same_account_fields() {
+    let owner = new_pubkey();
+    let mint = new_pubkey();
+    let requests = [
+        (
+            "/send/token",
+            json!({ "destination": new_pubkey(), "mint": mint, "owner": owner, "amount": 5 }),
+        ),
+        (
+            "/send/token/ata",
+            json!({
+                "sender": owner,
+                "recipient": new_pubkey(),
+                "mint": mint,
+                "amount": 5,
+                "decimals": 6
+            }),
+        ),
+        (
+            "/send/token/batch",
+            json!({
+                "owner": owner,
+                "mint": mint,
+                "decimals": 6,
+                "entries": [{ "destination": new_pubkey(), "amount": 5 }]
+            }),
+        ),
+    ];
+
+    for (uri, body) in requests {
+        let (status, body) = post_json(uri, body).await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+        let transfer = match body["data"].get("results") {
+            Some(results) => results[0].clone(),
+            None => body["data"].clone(),
+        };
+        for key in ["program_id", "accounts", "instruction_data", "source", "destination_ata"] {
+            assert!(transfer.get(key).is_some(), "{} is missing {}", uri, key);
+        }
+        assert_eq!(transfer["accounts"][0]["pubkey"], transfer["source"], "{}", uri);
+        assert_eq!(transfer["accounts"][2]["pubkey"], transfer["destination_ata"], "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn pubkey_errors_explain_encoding_and_length() {
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": "0OIl", "to": new_pubkey(), "lamports": 1 }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_PUBKEY");
+    assert!(body["error"].as_str().unwrap().starts_with("Invalid from address: not valid base58"));
+
+    let (status, body) = post_json(
+        "/send/sol",
+        json!({ "from": new_pubkey(), "to": bs58::encode([1u8; 33]).into_string(), "lamports": 1 }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_PUBKEY");
+    assert_eq!(body["error"], "Invalid to address: must decode to 32 bytes, got 33");
+}
+
+#[tokio::test]
+async fn create_multisig_validates_threshold() {
+    let multisig = new_pubkey();
+    let signers = vec![new_pubkey(), new_pubkey(), new_pubkey()];
+    let (status, body) = post_json(
+        "/token/multisig/create",
+        json!({ "multisig": multisig, "signers": signers, "m": 2 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["program_id"], spl_token::ID.to_string());
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], multisig);
+    // multisig, rent sysvar, then each signer.
+    assert_eq!(body["data"]["accounts"].as_array().unwrap().len(), 5);
+    assert_eq!(body["data"]["multisig_space"], 355);
+
+    let (status, body) = post_json(
+        "/token/multisig/create",
+        json!({ "multisig": multisig, "signers": signers, "m": 4 }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+
+    let too_many: Vec<String> = (0..12).map(|_| new_pubkey()).collect();
+    let (status, body) = post_json(
+        "/token/multisig/create",
+        json!({ "multisig": multisig, "signers": too_many, "m": 1 }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn pretty_true_indents_json() {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/pubkey/validate?pretty=true")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "pubkey": new_pubkey() }).to_string()))
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+    assert!(text.contains("\n  \"success\": true"));
+    let body: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(body["data"]["valid"], true);
+}
+
+#[tokio::test]
+async fn validate_blockhash_reports_validity() {
+    let blockhash = solana_sdk::hash::Hash::new_unique().to_string();
+    let (status, body) = post_json("/blockhash/validate", json!({ "blockhash": blockhash })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["valid"], true);
+
+    let (status, body) =
+        post_json("/blockhash/validate", json!({ "blockhash": "not-a-hash" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["valid"], false);
+}
+
+#[tokio::test]
+async fn as_array_wraps_single_instruction() {
+    let body = json!({ "from": new_pubkey(), "to": new_pubkey(), "lamports": 5000 });
+    let (_, single) = post_json("/send/sol", body.clone()).await;
+    let (status, array) = post_json("/send/sol?as_array=true", body).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(array["data"], json!({ "instructions": [single["data"]] }));
+}
+
+#[tokio::test]
+async fn as_array_keeps_endpoint_extras_next_to_instructions() {
+    let authority = new_pubkey();
+    let body = json!({ "mintAuthority": authority, "mint": authority, "decimals": 6 });
+    let (_, single) = post_json("/token/create", body.clone()).await;
+    let (status, array) = post_json("/token/create?as_array=true", body).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let instructions = array["data"]["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0]["accounts"], single["data"]["accounts"]);
+    assert!(instructions[0].get("mint_space").is_none());
+    for key in ["mint_space", "rent_exempt_lamports", "warnings"] {
+        assert_eq!(array["data"][key], single["data"][key], "{}", key);
+    }
+
+    let (_, mint) = post_json(
+        "/token/mint?as_array=true",
+        json!({
+            "mint": new_pubkey(),
+            "destination": new_pubkey(),
+            "authority": new_pubkey(),
+            "amount": 1
+        }),
+    )
+    .await;
+    assert_eq!(mint["data"]["labeled_accounts"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn slow_handler_times_out_with_408() {
+    let state = AppState {
+        request_timeout: std::time::Duration::from_millis(50),
+        ..AppState::default()
+    };
+    let app = axum::Router::new()
+        .route(
+            "/slow",
+            axum::routing::get(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                "done"
+            }),
+        )
+        .layer(axum::middleware::from_fn_with_state(state, middleware::timeout));
+
+    let request = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], "REQUEST_TIMEOUT");
+}
+
+#[tokio::test]
+async fn vanity_keypair_matches_prefix() {
+    let (status, body) = post_json("/keypair/vanity", json!({ "prefix": "A" })).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let pubkey = body["data"]["pubkey"].as_str().unwrap();
+    assert!(pubkey.starts_with('A'));
+    assert!(body["data"]["attempts"].as_u64().unwrap() >= 1);
+    let secret = bs58::decode(body["data"]["secret"].as_str().unwrap())
+        .into_vec()
+        .unwrap();
+    assert_eq!(Keypair::from_bytes(&secret).unwrap().pubkey().to_string(), pubkey);
+}
+
+#[tokio::test]
+async fn vanity_keypair_rejects_unreachable_prefixes() {
+    let (status, body) = post_json("/keypair/vanity", json!({ "prefix": "abcde" })).await;
+    assert_error(status, &body, "INVALID_INPUT");
+
+    let (status, body) = post_json("/keypair/vanity", json!({ "prefix": "0" })).await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn drain_sol_transfers_balance_minus_reserve() {
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/send/sol/drain",
+        json!({ "from": from.to_string(), "to": to.to_string(), "balance": 1_000_000 }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected = solana_sdk::system_instruction::transfer(&from, &to, 995_000);
+    assert_eq!(body["data"]["instruction_data"], base64::encode(&expected.data));
+
+    let (status, body) = post_json(
+        "/send/sol/drain",
+        json!({
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "balance": 10_000,
+            "fee_reserve": 10_000
+        }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_AMOUNT");
+}
+
+#[tokio::test]
+async fn format_amount_groups_and_trims() {
+    let cases = [
+        (json!({ "raw": 1_234_560_000u64, "decimals": 6, "symbol": "USDC" }), "1,234.56 USDC"),
+        (json!({ "raw": 1000, "decimals": 0 }), "1,000"),
+        (json!({ "raw": 5, "decimals": 6 }), "0.000005"),
+        (json!({ "raw": "18446744073709551615", "decimals": 9 }), "18,446,744,073.709551615"),
+    ];
+    for (request, expected) in cases {
+        let (status, body) = post_json("/token/amount/format", request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["display"], expected);
+    }
+}
+
+#[tokio::test]
+async fn deterministic_keypairs_repeat_across_instances() {
+    let first_pubkeys = || async {
+        let state = AppState {
+            keypair_counter: Some(std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0))),
+            ..AppState::default()
+        };
+        let app = axum::Router::new()
+            .route(
+                "/keypair",
+                axum::routing::post(solana_axum_server::handlers::generate_keypair),
+            )
+            .with_state(state);
+
+        let mut pubkeys = Vec::new();
+        for _ in 0..2 {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/keypair")
+                .body(Body::empty())
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let body: Value = serde_json::from_slice(&bytes).unwrap();
+            pubkeys.push(body["data"]["pubkey"].as_str().unwrap().to_string());
+        }
+        pubkeys
+    };
+
+    let run_a = first_pubkeys().await;
+    let run_b = first_pubkeys().await;
+    assert_eq!(run_a, run_b);
+    assert_ne!(run_a[0], run_a[1]);
+}
+
+#[tokio::test]
+async fn initialize_account_builds_instruction_with_rent() {
+    let account = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (status, body) = post_json(
+        "/token/account/init",
+        json!({
+            "account": account.to_string(),
+            "mint": mint.to_string(),
+            "owner": owner.to_string()
+        }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let expected =
+        spl_token::instruction::initialize_account(&spl_token::ID, &account, &mint, &owner)
+            .unwrap();
+    assert_eq!(body["data"]["instruction_data"], base64::encode(&expected.data));
+    assert_eq!(body["data"]["accounts"][0]["pubkey"], account.to_string());
+    assert_eq!(body["data"]["account_space"], 165);
+    assert_eq!(
+        body["data"]["rent_exempt_lamports"],
+        solana_sdk::rent::Rent::default().minimum_balance(165)
+    );
+}
+
+#[tokio::test]
+async fn token_handlers_use_configured_token_program() {
+    let state = AppState {
+        token_program: spl_token_2022::ID,
+        ..AppState::default()
+    };
+    let app = axum::Router::new()
+        .route(
+            "/token/transfer",
+            axum::routing::post(solana_axum_server::handlers::transfer_token),
+        )
+        .with_state(state);
+
+    let body = json!({
+        "source": new_pubkey(),
+        "destination": new_pubkey(),
+        "owner": new_pubkey(),
+        "amount": 5
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/token/transfer")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["data"]["program_id"], spl_token_2022::ID.to_string());
+}
+
+#[tokio::test]
+async fn program_selectable_endpoints_default_to_configured_token_program() {
+    let state = AppState {
+        token_program: spl_token_2022::ID,
+        ..AppState::default()
+    };
+    let app = solana_axum_server::router(state, None, false);
+
+    let requests = [
+        (
+            "/token/create",
+            json!({ "mintAuthority": new_pubkey(), "mint": new_pubkey(), "decimals": 6 }),
+        ),
+        (
+            "/token/mint",
+            json!({
+                "mint": new_pubkey(),
+                "destination": new_pubkey(),
+                "authority": new_pubkey(),
+                "amount": 5
+            }),
+        ),
+        (
+            "/send/token",
+            json!({
+                "destination": new_pubkey(),
+                "mint": new_pubkey(),
+                "owner": new_pubkey(),
+                "amount": 5
+            }),
+        ),
+    ];
+    for (uri, body) in requests {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["program_id"], spl_token_2022::ID.to_string(), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn arithmetic_overflow_is_reported_not_wrapped() {
+    let (status, body) = post_json(
+        "/transaction/fit-check",
+        json!({ "instructions": [], "signer_count": usize::MAX }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_AMOUNT");
+    assert!(body["error"].as_str().unwrap().starts_with("Arithmetic overflow"));
+
+    let (status, body) = post_json(
+        "/fees/estimate",
+        json!({ "instruction_count": 1, "signature_count": u64::MAX }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_AMOUNT");
+    assert!(body["error"].as_str().unwrap().starts_with("Arithmetic overflow"));
+}
+
+#[tokio::test]
+async fn large_batch_responses_are_gzip_compressed() {
+    let keypair = Keypair::new();
+    let messages: Vec<String> = (0..200).map(|i| format!("message {}", i)).collect();
+    let body = json!({
+        "secret": bs58::encode(keypair.to_bytes()).into_string(),
+        "messages": messages
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/message/sign-batch")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+}
+
+#[tokio::test]
+async fn keypair_selftest_passes() {
+    let (status, body) = send(Method::POST, "/keypair/selftest", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["ok"], true);
+}
+
+#[tokio::test]
+async fn sign_and_verify_reject_oversized_messages() {
+    let keypair = Keypair::new();
+    let message = "a".repeat(64 * 1024 + 1);
+
+    let (status, body) = post_json(
+        "/message/sign",
+        json!({ "message": message, "secret": bs58::encode(keypair.to_bytes()).into_string() }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+    assert!(body["error"].as_str().unwrap().starts_with("Message too large"));
+
+    let (status, body) = post_json(
+        "/message/verify",
+        json!({
+            "message": message,
+            "signature": base64::encode([0u8; 64]),
+            "pubkey": keypair.pubkey().to_string()
+        }),
+    )
+    .await;
+    assert_error(status, &body, "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn custom_instruction_round_trips() {
+    let program_id = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let instruction = solana_sdk::instruction::Instruction::new_with_bytes(
+        program_id,
+        &[1, 2, 3],
+        vec![solana_sdk::instruction::AccountMeta::new(account, true)],
+    );
+    let (status, body) = post_json("/instructions/custom", instruction_json(&instruction)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["program_id"], program_id.to_string());
+    assert_eq!(
+        body["data"]["accounts"],
+        json!([{ "pubkey": account.to_string(), "is_signer": true, "is_writable": true }])
+    );
+    assert_eq!(body["data"]["instruction_data"], base64::encode([1, 2, 3]));
+
+    let mut bad = instruction_json(&instruction);
+    bad["accounts"][0]["pubkey"] = json!("not-a-key");
+    let (status, body) = post_json("/instructions/custom", bad).await;
+    assert_error(status, &body, "INVALID_PUBKEY");
+}
+
+#[tokio::test]
+async fn stats_counts_produced_keypairs_and_signatures() {
+    let app = app();
+    let call = |method: Method, uri: &str, body: Option<Value>| {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+        app.clone().oneshot(request)
+    };
+
+    call(Method::POST, "/keypair", None).await.unwrap();
+    let secret = bs58::encode(Keypair::new().to_bytes()).into_string();
+    call(
+        Method::POST,
+        "/message/sign-batch",
+        Some(json!({ "secret": secret, "messages": ["a", "b"] })),
+    )
+    .await
+    .unwrap();
+
+    let response = call(Method::GET, "/stats", None).await.unwrap();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["data"], json!({ "keypairs": 1, "tokens": 0, "signatures": 2 }));
+}